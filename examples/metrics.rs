@@ -27,8 +27,16 @@ async fn show_metrics(report: IndexerReport) {
     // Can be used to track response status of RPC node during indexing process
     let metrics = report.get_metrics();
 
+    // Can be used to track time spent waiting for the RPC rate limiter
+    let throttle_metrics = report.get_throttle_metrics();
+
     let mut registry = Registry::default();
     registry.register("requests", "Count of requests", metrics);
+    registry.register(
+        "throttle_wait_seconds",
+        "Time spent waiting for the rate limiter",
+        throttle_metrics,
+    );
 
     loop {
         println!("Indexing status:\n{:?}", state.read().await);
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS cursors (
+    name VARCHAR PRIMARY KEY,
+    slot BIGINT,
+    signature VARCHAR,
+    updated_at BIGINT NOT NULL
+);
@@ -0,0 +1,10 @@
+DO $$ 
+BEGIN CREATE TYPE tx_commitment AS ENUM ('confirmed', 'finalized', 'dropped');
+EXCEPTION
+WHEN duplicate_object THEN null;
+END $$;
+
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS slot BIGINT;
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS commitment tx_commitment NOT NULL DEFAULT 'finalized';
+
+CREATE INDEX IF NOT EXISTS transactions_confirmed_idx ON transactions (slot) WHERE commitment = 'confirmed';
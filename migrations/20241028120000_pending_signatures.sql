@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS pending_signatures (
+    id BIGSERIAL PRIMARY KEY,
+    signature VARCHAR NOT NULL UNIQUE,
+    slot BIGINT NOT NULL,
+    status VARCHAR NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS pending_signatures_order_idx ON pending_signatures (slot, id DESC);
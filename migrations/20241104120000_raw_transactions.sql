@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS raw_transactions (
+    hash VARCHAR PRIMARY KEY,
+    slot BIGINT NOT NULL,
+    data BYTEA NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS raw_transactions_slot_idx ON raw_transactions (slot, hash);
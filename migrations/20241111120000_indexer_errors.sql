@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS indexer_errors (
+    id BIGSERIAL PRIMARY KEY,
+    message VARCHAR NOT NULL,
+    occurred_at BIGINT NOT NULL
+);
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS dead_letters (
+    id BIGSERIAL PRIMARY KEY,
+    signature VARCHAR,
+    slot BIGINT,
+    instruction SMALLINT,
+    category VARCHAR NOT NULL,
+    message VARCHAR NOT NULL,
+    occurred_at BIGINT NOT NULL
+);
@@ -0,0 +1,17 @@
+DO $$ 
+BEGIN CREATE TYPE backfill_status AS ENUM ('pending', 'claimed', 'completed');
+EXCEPTION
+WHEN duplicate_object THEN null;
+END $$;
+
+CREATE TABLE IF NOT EXISTS backfill_units (
+    id BIGSERIAL PRIMARY KEY,
+    start_slot BIGINT NOT NULL,
+    end_slot BIGINT NOT NULL,
+    status backfill_status NOT NULL DEFAULT 'pending',
+    worker VARCHAR,
+    claimed_at BIGINT,
+    UNIQUE (start_slot, end_slot)
+);
+
+CREATE INDEX IF NOT EXISTS backfill_units_claim_idx ON backfill_units (status, start_slot);
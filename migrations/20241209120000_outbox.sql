@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS outbox (
+    id BIGSERIAL PRIMARY KEY,
+    idempotency_key VARCHAR NOT NULL UNIQUE,
+    topic VARCHAR NOT NULL,
+    payload VARCHAR NOT NULL,
+    created_at BIGINT NOT NULL,
+    attempts INT NOT NULL DEFAULT 0,
+    last_error VARCHAR,
+    delivered_at BIGINT
+);
+
+CREATE INDEX IF NOT EXISTS outbox_undelivered_idx ON outbox (id) WHERE delivered_at IS NULL;
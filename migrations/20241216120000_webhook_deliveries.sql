@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id BIGSERIAL PRIMARY KEY,
+    url VARCHAR NOT NULL,
+    keys VARCHAR[] NOT NULL,
+    attempt INT NOT NULL,
+    status SMALLINT,
+    error VARCHAR,
+    occurred_at BIGINT NOT NULL
+);
@@ -0,0 +1,5 @@
+ALTER TABLE outbox ADD COLUMN IF NOT EXISTS dead_lettered_at BIGINT;
+
+DROP INDEX IF EXISTS outbox_undelivered_idx;
+CREATE INDEX IF NOT EXISTS outbox_undelivered_idx ON outbox (topic, id)
+    WHERE delivered_at IS NULL AND dead_lettered_at IS NULL;
@@ -0,0 +1,8 @@
+ALTER TABLE pending_signatures ADD COLUMN IF NOT EXISTS scope VARCHAR NOT NULL DEFAULT '';
+
+ALTER TABLE pending_signatures DROP CONSTRAINT IF EXISTS pending_signatures_signature_key;
+CREATE UNIQUE INDEX IF NOT EXISTS pending_signatures_scope_signature_idx
+    ON pending_signatures (scope, signature);
+
+DROP INDEX IF EXISTS pending_signatures_order_idx;
+CREATE INDEX IF NOT EXISTS pending_signatures_order_idx ON pending_signatures (scope, slot, id DESC);
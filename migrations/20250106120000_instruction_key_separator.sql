@@ -0,0 +1,2 @@
+UPDATE instructions SET id = tx_hash || ':' || substr(id, length(tx_hash) + 1)
+    WHERE id NOT LIKE tx_hash || ':%';
@@ -0,0 +1,17 @@
+use thiserror::Error;
+
+pub type ArchiveResult<T> = std::result::Result<T, ArchiveError>;
+
+/// Errors that are emitted while exporting or importing an archive
+#[derive(Error, Debug)]
+pub enum ArchiveError {
+    #[error(transparent)]
+    DbError(#[from] sqlx::Error),
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+    #[cfg(feature = "parquet")]
+    #[error(transparent)]
+    ParquetError(#[from] parquet::errors::ParquetError),
+}
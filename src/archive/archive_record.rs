@@ -0,0 +1,256 @@
+use {
+    serde::{de::DeserializeOwned, Deserialize, Serialize},
+    solana_sdk::clock::{Slot, UnixTimestamp},
+};
+
+#[cfg(feature = "parquet")]
+use {
+    super::{
+        parquet_reader::{read_optional_long, read_optional_string},
+        parquet_writer::{utf8, write_optional, write_required, RowGroupWriter},
+    },
+    parquet::{
+        data_type::{ByteArrayType, Int64Type},
+        errors::{ParquetError, Result as ParquetResult},
+        record::{Row, RowAccessor},
+    },
+};
+
+/// Range of the archived data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveRange {
+    /// Transactions of the slots between two slots, both inclusive
+    Slots { start: Slot, end: Slot },
+
+    /// Transactions with blocktime between two timestamps, both inclusive
+    Timestamps {
+        start: UnixTimestamp,
+        end: UnixTimestamp,
+    },
+}
+
+/// File format of the archive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    /// Newline-delimited JSON, one record per line
+    JsonLines,
+
+    /// Apache Parquet with a fixed schema
+    #[cfg(feature = "parquet")]
+    Parquet,
+}
+
+impl ArchiveFormat {
+    /// Returns the extension of archive files
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ArchiveFormat::JsonLines => "jsonl",
+            #[cfg(feature = "parquet")]
+            ArchiveFormat::Parquet => "parquet",
+        }
+    }
+}
+
+/// Amount of records written to or read from an archive
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ArchiveSummary {
+    pub transactions: u64,
+    pub instructions: u64,
+}
+
+/// A record of the archive stored in a file of its own
+pub trait ArchiveRecord: Serialize + DeserializeOwned + Send {
+    /// Name of the archive file without extension
+    const NAME: &'static str;
+
+    /// Parquet message type of the record
+    #[cfg(feature = "parquet")]
+    const SCHEMA: &'static str;
+
+    /// Writes columns of the records in the order of the schema
+    #[cfg(feature = "parquet")]
+    fn write_row_group(records: &[Self], row_group: &mut RowGroupWriter<'_>) -> ParquetResult<()>;
+
+    /// Reads the record from a row of the schema
+    #[cfg(feature = "parquet")]
+    fn read_row(row: &Row) -> ParquetResult<Self>;
+}
+
+/// An archived row of the `transactions` table
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TransactionRecord {
+    /// Transaction signature hash
+    pub hash: String,
+
+    /// Slot of transaction block, absent for transactions indexed before slots were stored
+    pub slot: Option<Slot>,
+
+    /// Time of transaction block
+    pub blocktime: UnixTimestamp,
+
+    /// Internal indexing status of transaction
+    pub indexing_status: String,
+
+    /// Commitment level the transaction was observed at
+    pub commitment: String,
+
+    /// Timestamp when indexing was conducted
+    pub indexing_timestamp: UnixTimestamp,
+
+    /// Fetched transaction as it was returned by RPC node, present if raw transactions are stored
+    pub raw: Option<serde_json::Value>,
+}
+
+impl ArchiveRecord for TransactionRecord {
+    const NAME: &'static str = "transactions";
+
+    #[cfg(feature = "parquet")]
+    const SCHEMA: &'static str = "
+        message transaction {
+            REQUIRED BYTE_ARRAY hash (UTF8);
+            OPTIONAL INT64 slot;
+            REQUIRED INT64 blocktime;
+            REQUIRED BYTE_ARRAY indexing_status (UTF8);
+            REQUIRED BYTE_ARRAY commitment (UTF8);
+            REQUIRED INT64 indexing_timestamp;
+            OPTIONAL BYTE_ARRAY raw (UTF8);
+        }
+    ";
+
+    #[cfg(feature = "parquet")]
+    fn write_row_group(records: &[Self], row_group: &mut RowGroupWriter<'_>) -> ParquetResult<()> {
+        write_required::<ByteArrayType>(
+            row_group,
+            records.iter().map(|record| utf8(&record.hash)).collect(),
+        )?;
+        write_optional::<Int64Type>(
+            row_group,
+            records
+                .iter()
+                .map(|record| record.slot.map(|slot| slot as i64))
+                .collect(),
+        )?;
+        write_required::<Int64Type>(
+            row_group,
+            records.iter().map(|record| record.blocktime).collect(),
+        )?;
+        write_required::<ByteArrayType>(
+            row_group,
+            records
+                .iter()
+                .map(|record| utf8(&record.indexing_status))
+                .collect(),
+        )?;
+        write_required::<ByteArrayType>(
+            row_group,
+            records
+                .iter()
+                .map(|record| utf8(&record.commitment))
+                .collect(),
+        )?;
+        write_required::<Int64Type>(
+            row_group,
+            records
+                .iter()
+                .map(|record| record.indexing_timestamp)
+                .collect(),
+        )?;
+        write_optional::<ByteArrayType>(
+            row_group,
+            records
+                .iter()
+                .map(|record| record.raw.as_ref().map(|raw| utf8(&raw.to_string())))
+                .collect(),
+        )
+    }
+
+    #[cfg(feature = "parquet")]
+    fn read_row(row: &Row) -> ParquetResult<Self> {
+        let raw = read_optional_string(row, 6)?
+            .map(|raw| serde_json::from_str(&raw))
+            .transpose()
+            .map_err(|error| ParquetError::External(Box::new(error)))?;
+
+        Ok(Self {
+            hash: row.get_string(0)?.clone(),
+            slot: read_optional_long(row, 1)?.map(|slot| slot as Slot),
+            blocktime: row.get_long(2)?,
+            indexing_status: row.get_string(3)?.clone(),
+            commitment: row.get_string(4)?.clone(),
+            indexing_timestamp: row.get_long(5)?,
+            raw,
+        })
+    }
+}
+
+/// An archived row of the `instructions` table
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct InstructionRecord {
+    /// Instruction identifier
+    pub id: String,
+
+    /// Signature hash of the instruction transaction
+    pub tx_hash: String,
+
+    /// The public key of the invoked program
+    pub program_id: String,
+
+    /// Time of transaction block
+    pub blocktime: UnixTimestamp,
+
+    /// Encoded instruction data
+    pub data: String,
+}
+
+impl ArchiveRecord for InstructionRecord {
+    const NAME: &'static str = "instructions";
+
+    #[cfg(feature = "parquet")]
+    const SCHEMA: &'static str = "
+        message instruction {
+            REQUIRED BYTE_ARRAY id (UTF8);
+            REQUIRED BYTE_ARRAY tx_hash (UTF8);
+            REQUIRED BYTE_ARRAY program_id (UTF8);
+            REQUIRED INT64 blocktime;
+            REQUIRED BYTE_ARRAY data (UTF8);
+        }
+    ";
+
+    #[cfg(feature = "parquet")]
+    fn write_row_group(records: &[Self], row_group: &mut RowGroupWriter<'_>) -> ParquetResult<()> {
+        write_required::<ByteArrayType>(
+            row_group,
+            records.iter().map(|record| utf8(&record.id)).collect(),
+        )?;
+        write_required::<ByteArrayType>(
+            row_group,
+            records.iter().map(|record| utf8(&record.tx_hash)).collect(),
+        )?;
+        write_required::<ByteArrayType>(
+            row_group,
+            records
+                .iter()
+                .map(|record| utf8(&record.program_id))
+                .collect(),
+        )?;
+        write_required::<Int64Type>(
+            row_group,
+            records.iter().map(|record| record.blocktime).collect(),
+        )?;
+        write_required::<ByteArrayType>(
+            row_group,
+            records.iter().map(|record| utf8(&record.data)).collect(),
+        )
+    }
+
+    #[cfg(feature = "parquet")]
+    fn read_row(row: &Row) -> ParquetResult<Self> {
+        Ok(Self {
+            id: row.get_string(0)?.clone(),
+            tx_hash: row.get_string(1)?.clone(),
+            program_id: row.get_string(2)?.clone(),
+            blocktime: row.get_long(3)?,
+            data: row.get_string(4)?.clone(),
+        })
+    }
+}
@@ -0,0 +1,142 @@
+use {
+    super::{
+        ArchiveError, ArchiveFormat, ArchiveRange, ArchiveRecord, ArchiveResult, ArchiveSummary,
+        InstructionRecord, TransactionRecord,
+    },
+    crate::{
+        configuration::{ConfigOverrides, Configuration},
+        db::DbManager,
+        fetcher::decompress_raw_tx,
+        IndexingResult,
+    },
+    futures::{stream::BoxStream, StreamExt},
+    std::{marker::PhantomData, path::Path},
+    tokio::{
+        fs::{self, File},
+        io::{AsyncWriteExt, BufWriter},
+    },
+    tracing::info,
+};
+
+#[cfg(feature = "parquet")]
+use super::parquet_writer::ParquetWriter;
+
+/// Writes records of a single type into an archive file
+enum RecordWriter<R> {
+    JsonLines(BufWriter<File>, PhantomData<R>),
+    #[cfg(feature = "parquet")]
+    Parquet(ParquetWriter<R>),
+}
+
+impl<R: ArchiveRecord> RecordWriter<R> {
+    async fn create(dir: &Path, format: ArchiveFormat) -> ArchiveResult<Self> {
+        let path = dir.join(format!("{}.{}", R::NAME, format.extension()));
+
+        Ok(match format {
+            ArchiveFormat::JsonLines => {
+                RecordWriter::JsonLines(BufWriter::new(File::create(path).await?), PhantomData)
+            }
+            #[cfg(feature = "parquet")]
+            ArchiveFormat::Parquet => RecordWriter::Parquet(ParquetWriter::create(&path)?),
+        })
+    }
+
+    async fn write(&mut self, record: R) -> ArchiveResult<()> {
+        match self {
+            RecordWriter::JsonLines(writer, _) => {
+                let mut line = serde_json::to_vec(&record)?;
+                line.push(b'\n');
+                writer.write_all(&line).await?;
+            }
+            #[cfg(feature = "parquet")]
+            RecordWriter::Parquet(writer) => writer.write(record)?,
+        }
+        Ok(())
+    }
+
+    async fn finish(self) -> ArchiveResult<()> {
+        match self {
+            RecordWriter::JsonLines(mut writer, _) => writer.flush().await?,
+            #[cfg(feature = "parquet")]
+            RecordWriter::Parquet(writer) => writer.finish()?,
+        }
+        Ok(())
+    }
+}
+
+/// Streams indexed transactions and instructions into archive files
+pub struct Exporter {
+    /// Responsible for database interaction
+    db_manager: DbManager,
+}
+
+impl Exporter {
+    /// Creates new instance of exporter connected to the configured database
+    pub fn build() -> IndexingResult<Self> {
+        let settings = Configuration::load(&ConfigOverrides::default())?;
+
+        Ok(Self::new(DbManager::connect(
+            settings.db_settings.with_db(),
+        )?))
+    }
+
+    pub(crate) fn new(db_manager: DbManager) -> Self {
+        Self { db_manager }
+    }
+
+    /// Writes `transactions` and `instructions` files of the range into the directory
+    #[tracing::instrument(level = "info", skip(self))]
+    pub async fn export(
+        &self,
+        range: ArchiveRange,
+        format: ArchiveFormat,
+        dir: &Path,
+    ) -> ArchiveResult<ArchiveSummary> {
+        fs::create_dir_all(dir).await?;
+
+        let transactions = self
+            .db_manager
+            .stream_transaction_records(range)
+            .map(|item| -> ArchiveResult<TransactionRecord> {
+                let (mut record, data) = item?;
+                if let Some(data) = data {
+                    record.raw = Some(serde_json::to_value(decompress_raw_tx(&data)?)?);
+                }
+                Ok(record)
+            })
+            .boxed();
+        let transactions = export_records::<TransactionRecord>(transactions, format, dir).await?;
+
+        let instructions = self
+            .db_manager
+            .stream_instruction_records(range)
+            .map(|item| item.map_err(ArchiveError::from))
+            .boxed();
+        let instructions = export_records::<InstructionRecord>(instructions, format, dir).await?;
+
+        Ok(ArchiveSummary {
+            transactions,
+            instructions,
+        })
+    }
+}
+
+/// Writes all records of the stream into the archive file and returns their amount
+async fn export_records<R: ArchiveRecord>(
+    mut records: BoxStream<'_, ArchiveResult<R>>,
+    format: ArchiveFormat,
+    dir: &Path,
+) -> ArchiveResult<u64> {
+    let mut writer = RecordWriter::<R>::create(dir, format).await?;
+    let mut count = 0;
+
+    while let Some(record) = records.next().await {
+        writer.write(record?).await?;
+        count += 1;
+    }
+
+    writer.finish().await?;
+    info!(file = R::NAME, count, "Records exported");
+
+    Ok(count)
+}
@@ -0,0 +1,117 @@
+use {
+    super::{
+        ArchiveFormat, ArchiveRecord, ArchiveResult, ArchiveSummary, InstructionRecord,
+        TransactionRecord,
+    },
+    crate::{
+        configuration::{ConfigOverrides, Configuration},
+        db::{DbManager, IndexerDbRecording, RawTxRecord},
+        fetcher::compress_raw_tx,
+        EncodedConfirmedTransactionWithStatusMeta, IndexingResult,
+    },
+    std::{marker::PhantomData, path::Path},
+    tokio::{
+        fs::File,
+        io::{AsyncBufReadExt, BufReader, Lines},
+    },
+    tracing::info,
+};
+
+#[cfg(feature = "parquet")]
+use super::parquet_reader::ParquetReader;
+
+/// Reads records of a single type from an archive file
+enum RecordReader<R> {
+    JsonLines(Lines<BufReader<File>>, PhantomData<R>),
+    #[cfg(feature = "parquet")]
+    Parquet(ParquetReader<R>),
+}
+
+impl<R: ArchiveRecord> RecordReader<R> {
+    async fn open(dir: &Path, format: ArchiveFormat) -> ArchiveResult<Self> {
+        let path = dir.join(format!("{}.{}", R::NAME, format.extension()));
+
+        Ok(match format {
+            ArchiveFormat::JsonLines => RecordReader::JsonLines(
+                BufReader::new(File::open(path).await?).lines(),
+                PhantomData,
+            ),
+            #[cfg(feature = "parquet")]
+            ArchiveFormat::Parquet => RecordReader::Parquet(ParquetReader::open(&path)?),
+        })
+    }
+
+    /// Returns the next record or `None` once the file is read
+    async fn read(&mut self) -> ArchiveResult<Option<R>> {
+        match self {
+            RecordReader::JsonLines(lines, _) => {
+                while let Some(line) = lines.next_line().await? {
+                    if !line.trim().is_empty() {
+                        return Ok(Some(serde_json::from_str(&line)?));
+                    }
+                }
+                Ok(None)
+            }
+            #[cfg(feature = "parquet")]
+            RecordReader::Parquet(reader) => reader.read(),
+        }
+    }
+}
+
+/// Seeds a database from an archive without calling RPC node
+pub struct Importer {
+    /// Responsible for database interaction
+    db_manager: DbManager,
+}
+
+impl Importer {
+    /// Creates new instance of importer connected to the configured database
+    pub fn build() -> IndexingResult<Self> {
+        let settings = Configuration::load(&ConfigOverrides::default())?;
+
+        Ok(Self::new(DbManager::connect(
+            settings.db_settings.with_db(),
+        )?))
+    }
+
+    pub(crate) fn new(db_manager: DbManager) -> Self {
+        Self { db_manager }
+    }
+
+    /// Reads `transactions` and `instructions` files of the directory into the database,
+    /// rows that are already present are left untouched
+    #[tracing::instrument(level = "info", skip(self))]
+    pub async fn import(&self, dir: &Path, format: ArchiveFormat) -> ArchiveResult<ArchiveSummary> {
+        let mut transactions = 0;
+        let mut reader = RecordReader::<TransactionRecord>::open(dir, format).await?;
+        while let Some(record) = reader.read().await? {
+            self.db_manager.insert_transaction_record(&record).await?;
+
+            if let Some(raw) = record.raw {
+                let raw_tx =
+                    serde_json::from_value::<EncodedConfirmedTransactionWithStatusMeta>(raw)?;
+                let raw_record = RawTxRecord {
+                    hash: record.hash,
+                    slot: raw_tx.slot,
+                    data: compress_raw_tx(&raw_tx)?,
+                };
+                self.db_manager.insert_raw_transaction(&raw_record).await?;
+            }
+            transactions += 1;
+        }
+        info!(count = transactions, "Transactions imported");
+
+        let mut instructions = 0;
+        let mut reader = RecordReader::<InstructionRecord>::open(dir, format).await?;
+        while let Some(record) = reader.read().await? {
+            self.db_manager.insert_instruction_record(&record).await?;
+            instructions += 1;
+        }
+        info!(count = instructions, "Instructions imported");
+
+        Ok(ArchiveSummary {
+            transactions,
+            instructions,
+        })
+    }
+}
@@ -0,0 +1,16 @@
+pub mod archive_error;
+pub mod archive_record;
+pub mod exporter;
+pub mod importer;
+#[cfg(feature = "parquet")]
+pub mod parquet_reader;
+#[cfg(feature = "parquet")]
+pub mod parquet_writer;
+
+#[cfg(test)]
+mod tests;
+
+pub use archive_error::*;
+pub use archive_record::*;
+pub use exporter::*;
+pub use importer::*;
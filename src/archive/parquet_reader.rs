@@ -0,0 +1,65 @@
+use {
+    super::{ArchiveRecord, ArchiveResult},
+    parquet::{
+        errors::{ParquetError, Result as ParquetResult},
+        file::reader::SerializedFileReader,
+        record::{reader::RowIter, Field, Row},
+    },
+    std::{fs::File, marker::PhantomData, path::Path},
+};
+
+/// Reads records of a single type from a Parquet file written by
+/// [ParquetWriter](super::parquet_writer::ParquetWriter)
+pub struct ParquetReader<R> {
+    rows: RowIter<'static>,
+    record: PhantomData<R>,
+}
+
+impl<R: ArchiveRecord> ParquetReader<R> {
+    pub fn open(path: &Path) -> ArchiveResult<Self> {
+        let reader = SerializedFileReader::new(File::open(path)?)?;
+
+        Ok(Self {
+            rows: reader.into_iter(),
+            record: PhantomData,
+        })
+    }
+
+    /// Returns the next record or `None` once the file is read
+    pub fn read(&mut self) -> ArchiveResult<Option<R>> {
+        let Some(row) = self.rows.next() else {
+            return Ok(None);
+        };
+
+        Ok(Some(R::read_row(&row?)?))
+    }
+}
+
+/// Returns the value of the optional INT64 column of the row
+pub fn read_optional_long(row: &Row, index: usize) -> ParquetResult<Option<i64>> {
+    match column(row, index)? {
+        Field::Null => Ok(None),
+        Field::Long(value) => Ok(Some(*value)),
+        field => Err(unexpected(index, field)),
+    }
+}
+
+/// Returns the value of the optional UTF8 column of the row
+pub fn read_optional_string(row: &Row, index: usize) -> ParquetResult<Option<String>> {
+    match column(row, index)? {
+        Field::Null => Ok(None),
+        Field::Str(value) => Ok(Some(value.clone())),
+        field => Err(unexpected(index, field)),
+    }
+}
+
+fn column(row: &Row, index: usize) -> ParquetResult<&Field> {
+    row.get_column_iter()
+        .nth(index)
+        .map(|(_, field)| field)
+        .ok_or_else(|| ParquetError::General(format!("Column {index} is absent in the row")))
+}
+
+fn unexpected(index: usize, field: &Field) -> ParquetError {
+    ParquetError::General(format!("Column {index} has unexpected value {field}"))
+}
@@ -0,0 +1,107 @@
+use {
+    super::{ArchiveRecord, ArchiveResult},
+    parquet::{
+        data_type::{ByteArray, DataType},
+        errors::{ParquetError, Result as ParquetResult},
+        file::{
+            properties::WriterProperties,
+            writer::{SerializedFileWriter, SerializedRowGroupWriter},
+        },
+        schema::parser::parse_message_type,
+    },
+    std::{fs::File, path::Path, sync::Arc},
+};
+
+/// Amount of records written in one row group
+const ROW_GROUP_SIZE: usize = 10_000;
+
+pub type RowGroupWriter<'a> = SerializedRowGroupWriter<'a, File>;
+
+/// Writes records of a single type into a Parquet file with the schema of the record
+pub struct ParquetWriter<R> {
+    writer: SerializedFileWriter<File>,
+
+    /// Records of the row group that is not written yet
+    rows: Vec<R>,
+}
+
+impl<R: ArchiveRecord> ParquetWriter<R> {
+    pub fn create(path: &Path) -> ArchiveResult<Self> {
+        let schema = Arc::new(parse_message_type(R::SCHEMA)?);
+        let properties = Arc::new(WriterProperties::builder().build());
+
+        Ok(Self {
+            writer: SerializedFileWriter::new(File::create(path)?, schema, properties)?,
+            rows: Vec::with_capacity(ROW_GROUP_SIZE),
+        })
+    }
+
+    pub fn write(&mut self, record: R) -> ArchiveResult<()> {
+        self.rows.push(record);
+
+        if self.rows.len() >= ROW_GROUP_SIZE {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Writes the remaining records and the file footer
+    pub fn finish(mut self) -> ArchiveResult<()> {
+        self.flush()?;
+        self.writer.close()?;
+
+        Ok(())
+    }
+
+    fn flush(&mut self) -> ArchiveResult<()> {
+        if self.rows.is_empty() {
+            return Ok(());
+        }
+
+        let mut row_group = self.writer.next_row_group()?;
+        R::write_row_group(&self.rows, &mut row_group)?;
+        row_group.close()?;
+        self.rows.clear();
+
+        Ok(())
+    }
+}
+
+/// Writes values of the next required column of the row group
+pub fn write_required<T: DataType>(
+    row_group: &mut RowGroupWriter<'_>,
+    values: Vec<T::T>,
+) -> ParquetResult<()> {
+    let mut column = row_group
+        .next_column()?
+        .ok_or_else(|| ParquetError::General("Column is absent in the schema".to_string()))?;
+    column.typed::<T>().write_batch(&values, None, None)?;
+
+    column.close()
+}
+
+/// Writes values of the next optional column of the row group
+pub fn write_optional<T: DataType>(
+    row_group: &mut RowGroupWriter<'_>,
+    values: Vec<Option<T::T>>,
+) -> ParquetResult<()> {
+    let def_levels = values
+        .iter()
+        .map(|value| i16::from(value.is_some()))
+        .collect::<Vec<_>>();
+    let values = values.into_iter().flatten().collect::<Vec<_>>();
+
+    let mut column = row_group
+        .next_column()?
+        .ok_or_else(|| ParquetError::General("Column is absent in the schema".to_string()))?;
+    column
+        .typed::<T>()
+        .write_batch(&values, Some(&def_levels), None)?;
+
+    column.close()
+}
+
+/// Converts string into a Parquet UTF8 value
+pub fn utf8(value: &str) -> ByteArray {
+    ByteArray::from(value)
+}
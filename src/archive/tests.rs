@@ -0,0 +1,141 @@
+use {
+    super::*,
+    crate::{
+        db::{test_connection_manager::ConnectionManager, DbManager, IndexerDbRecording},
+        fetcher::{IndexingStatus, Tx, TxCommitment},
+        processor::Instruction,
+    },
+};
+
+fn get_tx(hash: &str, slot: u64, blocktime: i64) -> Tx {
+    Tx {
+        hash: hash.to_string(),
+        slot,
+        blocktime,
+        instructions: vec![],
+        account_keys: vec![],
+        indexing_status: IndexingStatus::Indexed,
+        commitment: TxCommitment::Finalized,
+        indexing_timestamp: 123,
+    }
+}
+
+async fn export_import(format: ArchiveFormat) {
+    let source = ConnectionManager::build().await;
+    let source_db =
+        DbManager::connect(source.get_connection_options()).expect("Failed to create manager");
+
+    for tx in [get_tx("first", 10, 100), get_tx("second", 20, 200)] {
+        source_db
+            .insert_transaction(&tx)
+            .await
+            .expect("Failed to insert transaction");
+    }
+    for tx_hash in ["first", "second"] {
+        let instruction = Instruction::new(
+            0,
+            tx_hash.to_string(),
+            "test_id".to_string(),
+            123,
+            vec![],
+            "empty_data".to_string(),
+        );
+        source_db
+            .insert_instruction(&instruction)
+            .await
+            .expect("Failed to insert instruction");
+    }
+
+    let dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+    let summary = Exporter::new(source_db)
+        .export(ArchiveRange::Slots { start: 0, end: 15 }, format, &dir)
+        .await
+        .expect("Failed to export archive");
+    assert_eq!(
+        summary,
+        ArchiveSummary {
+            transactions: 1,
+            instructions: 1,
+        }
+    );
+
+    let target = ConnectionManager::build().await;
+    let target_db =
+        DbManager::connect(target.get_connection_options()).expect("Failed to create manager");
+    let summary = Importer::new(target_db.clone())
+        .import(&dir, format)
+        .await
+        .expect("Failed to import archive");
+    assert_eq!(
+        summary,
+        ArchiveSummary {
+            transactions: 1,
+            instructions: 1,
+        }
+    );
+
+    assert!(target_db
+        .recorded_tx("first")
+        .await
+        .expect("Failed to get transaction"));
+    assert!(!target_db
+        .recorded_tx("second")
+        .await
+        .expect("Failed to get transaction"));
+
+    std::fs::remove_dir_all(dir).expect("Failed to remove archive");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn export_import_test() {
+    export_import(ArchiveFormat::JsonLines).await;
+}
+
+#[cfg(feature = "parquet")]
+#[tokio::test(flavor = "multi_thread")]
+async fn parquet_export_import_test() {
+    export_import(ArchiveFormat::Parquet).await;
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn parquet_records_test() {
+    use super::{parquet_reader::ParquetReader, parquet_writer::ParquetWriter};
+
+    let records = vec![
+        TransactionRecord {
+            hash: "first".to_string(),
+            slot: Some(10),
+            blocktime: 100,
+            indexing_status: "indexed".to_string(),
+            commitment: "finalized".to_string(),
+            indexing_timestamp: 123,
+            raw: Some(serde_json::json!({ "slot": 10 })),
+        },
+        TransactionRecord {
+            hash: "second".to_string(),
+            slot: None,
+            blocktime: 200,
+            indexing_status: "pending".to_string(),
+            commitment: "confirmed".to_string(),
+            indexing_timestamp: 456,
+            raw: None,
+        },
+    ];
+
+    let path = std::env::temp_dir().join(format!("{}.parquet", uuid::Uuid::new_v4()));
+    let mut writer = ParquetWriter::create(&path).expect("Failed to create file");
+    for record in records.clone() {
+        writer.write(record).expect("Failed to write record");
+    }
+    writer.finish().expect("Failed to finish file");
+
+    let mut reader = ParquetReader::<TransactionRecord>::open(&path).expect("Failed to open file");
+    let mut read = vec![];
+    while let Some(record) = reader.read().expect("Failed to read record") {
+        read.push(record);
+    }
+    assert_eq!(read, records);
+
+    std::fs::remove_file(path).expect("Failed to remove file");
+}
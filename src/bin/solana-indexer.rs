@@ -0,0 +1,85 @@
+use {
+    clap::{CommandFactory, FromArgMatches},
+    config::ConfigError,
+    solana_indexer::{
+        cli::{self, Cli, Command},
+        CallbackResult, ConfigOverrides, Configuration, ExecutorCallback, ExecutorControlFlow,
+        IndexingResult, Instruction,
+    },
+    std::process,
+};
+
+/// Stores fetched transactions without processing their instructions,
+/// so they can be processed later by `reindex` of an application with its own executor
+struct StoringExecutor;
+
+/// Shown in `--help`, every instruction is skipped by [StoringExecutor]
+const STORING_NOTE: &str = "\
+This binary skips every instruction: transactions are marked as indexed and stored \
+without being processed. Applications process instructions by running the same \
+commands through `solana_indexer::cli::run(executor)` with their own executor.";
+
+impl ExecutorCallback for StoringExecutor {
+    async fn process_instruction(
+        &mut self,
+        _instruction: &Instruction,
+    ) -> CallbackResult<ExecutorControlFlow> {
+        Ok(ExecutorControlFlow::Skip)
+    }
+}
+
+/// Refuses commands that would mark transactions as indexed
+/// while nothing is kept to process them later.
+/// Reindexing replays stored transactions into the executor, so it is left to applications.
+fn check_command(command: &Command) -> IndexingResult<()> {
+    match command {
+        Command::Reindex { .. } => Err(ConfigError::Message(
+            "reindex needs an executor processing instructions, run it through `cli::run(executor)`"
+                .to_string(),
+        )
+        .into()),
+        Command::Run | Command::Backfill { .. } | Command::BackfillWorker | Command::Repair => {
+            let stores_raw = Configuration::load(&ConfigOverrides::default())?
+                .fetcher_settings
+                .map_or(false, |settings| settings.store_raw_transactions);
+            if !stores_raw {
+                return Err(ConfigError::Message(
+                    "fetcher_settings.store_raw_transactions must be enabled, \
+                        otherwise transactions are marked as indexed without being processed"
+                        .to_string(),
+                )
+                .into());
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    #[cfg(feature = "otel")]
+    let _telemetry = match solana_indexer::telemetry::init_telemetry("solana-indexer", None) {
+        Ok(guard) => guard,
+        Err(err) => {
+            eprintln!("{err}");
+            process::exit(1);
+        }
+    };
+
+    let matches = Cli::command().after_help(STORING_NOTE).get_matches();
+    let command = match Cli::from_arg_matches(&matches) {
+        Ok(cli) => cli.command,
+        Err(err) => err.exit(),
+    };
+    let result = match check_command(&command) {
+        Ok(()) => cli::run_command(command, StoringExecutor).await,
+        Err(err) => Err(err),
+    };
+
+    if let Err(err) = result {
+        err.get_trace();
+        eprintln!("{err}");
+        process::exit(1);
+    }
+}
@@ -0,0 +1,167 @@
+use {
+    crate::{
+        ArchiveFormat, ArchiveRange, ExecutorCallback, Exporter, Indexer, IndexerEngine,
+        IndexingResult,
+    },
+    clap::{Parser, Subcommand, ValueEnum},
+    solana_sdk::clock::Slot,
+    std::path::PathBuf,
+};
+
+/// Operates the indexer with the configuration file defined in INDEXER_CFG env variable
+#[derive(Parser, Debug)]
+#[command(name = "solana-indexer", version)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Starts indexing
+    Run,
+
+    /// Runs database migrations
+    Migrate,
+
+    /// Prints the checkpoints, the amount of pending work and the last error
+    Status,
+
+    /// Plans the slot range, both inclusive, as backfill units and indexes them before exiting
+    Backfill {
+        #[arg(long)]
+        from: Slot,
+        #[arg(long)]
+        to: Slot,
+    },
+
+    /// Splits the slot range, both inclusive, into units for backfill workers
+    PlanBackfill {
+        #[arg(long)]
+        from: Slot,
+        #[arg(long)]
+        to: Slot,
+    },
+
+    /// Indexes planned backfill units until none are left, several workers can run at once
+    BackfillWorker,
+
+    /// Fetches and processes again transactions that were left unprocessed
+    Repair,
+
+    /// Replays stored transactions of the slot range, both inclusive, without RPC calls
+    Reindex {
+        #[arg(long)]
+        from: Slot,
+        #[arg(long)]
+        to: Slot,
+    },
+
+    /// Exports indexed data of the slot range, both inclusive, into the directory
+    Export {
+        #[arg(long)]
+        from: Slot,
+        #[arg(long)]
+        to: Slot,
+        #[arg(long, default_value = "archive")]
+        dir: PathBuf,
+        #[arg(long, value_enum, default_value_t = ExportFormat::Jsonl)]
+        format: ExportFormat,
+    },
+}
+
+/// File format of exported archive
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum ExportFormat {
+    Jsonl,
+    #[cfg(feature = "parquet")]
+    Parquet,
+}
+
+impl From<ExportFormat> for ArchiveFormat {
+    fn from(format: ExportFormat) -> Self {
+        match format {
+            ExportFormat::Jsonl => ArchiveFormat::JsonLines,
+            #[cfg(feature = "parquet")]
+            ExportFormat::Parquet => ArchiveFormat::Parquet,
+        }
+    }
+}
+
+/// Parses command line arguments and runs the command with the executor
+pub async fn run<E>(executor: E) -> IndexingResult<()>
+where
+    E: ExecutorCallback + Send + Sync + 'static,
+{
+    run_command(Cli::parse().command, executor).await
+}
+
+/// Runs the command with the executor
+pub async fn run_command<E>(command: Command, executor: E) -> IndexingResult<()>
+where
+    E: ExecutorCallback + Send + Sync + 'static,
+{
+    match command {
+        Command::Run => build_indexer(executor).await?.start_indexing().await?,
+        Command::Migrate => {
+            build_indexer(executor).await?.run_migrations().await?;
+            println!("Migrations are applied");
+        }
+        Command::Status => println!("{}", build_indexer(executor).await?.status().await?),
+        Command::Backfill { from, to } => {
+            // Backfill units don't share cursors with the live indexing nor wait for its lease
+            let mut indexer = build_indexer(executor).await?;
+            let planned = indexer.plan_backfill(from, to).await?;
+            let count = indexer.run_backfill_worker().await?;
+            println!("Planned {planned} and indexed {count} backfill units");
+        }
+        Command::PlanBackfill { from, to } => {
+            let count = build_indexer(executor)
+                .await?
+                .plan_backfill(from, to)
+                .await?;
+            println!("Planned {count} backfill units");
+        }
+        Command::BackfillWorker => {
+            let count = build_indexer(executor).await?.run_backfill_worker().await?;
+            println!("Indexed {count} backfill units");
+        }
+        Command::Repair => {
+            let count = build_indexer(executor).await?.repair().await?;
+            println!("Repaired {count} transactions");
+        }
+        Command::Reindex { from, to } => build_indexer(executor).await?.reindex(from, to).await?,
+        Command::Export {
+            from,
+            to,
+            dir,
+            format,
+        } => {
+            let range = ArchiveRange::Slots {
+                start: from,
+                end: to,
+            };
+            let summary = Exporter::build()?
+                .export(range, format.into(), &dir)
+                .await?;
+            println!(
+                "Exported {} transactions and {} instructions into {}",
+                summary.transactions,
+                summary.instructions,
+                dir.display()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn build_indexer<E>(executor: E) -> IndexingResult<Indexer<E>>
+where
+    E: ExecutorCallback + Send + Sync + 'static,
+{
+    let mut indexer = Indexer::build().await?;
+    indexer.set_executor(executor);
+
+    Ok(indexer)
+}
@@ -0,0 +1,184 @@
+pub mod validation;
+
+#[cfg(test)]
+mod tests;
+
+pub use validation::*;
+
+use config::{Config, ConfigBuilder, ConfigError, Environment, File, Map, Value};
+use serde::Deserialize;
+
+use crate::{db::DatabaseSettings, FetchingSettings, IndexerSettings, IndexingResult};
+
+/// Prefix and separator of environment variables overriding configuration fields,
+/// e.g. `INDEXER__DB_SETTINGS__PASSWORD` overrides `db_settings.password`
+const ENV_PREFIX: &str = "INDEXER";
+const ENV_SEPARATOR: &str = "__";
+
+/// Configurations for the indexer engine
+#[derive(Deserialize, Clone, Debug)]
+pub struct Configuration {
+    pub indexer_settings: IndexerSettings,
+    pub db_settings: DatabaseSettings,
+    pub fetcher_settings: Option<FetchingSettings>,
+}
+
+impl Configuration {
+    /// Loads the layered configuration, see [`get_configuration_with_overrides`]
+    pub fn load(overrides: &ConfigOverrides) -> IndexingResult<Self> {
+        Ok(Self::from_config(&layered_config(overrides)?)?)
+    }
+
+    /// Loads the layered configuration with the file at `path` instead of `INDEXER_CFG`
+    pub(crate) fn load_from(path: &str, overrides: &ConfigOverrides) -> IndexingResult<Self> {
+        let config = build_config(path, std::env::vars().collect(), overrides)?;
+
+        Ok(Self::from_config(&config)?)
+    }
+
+    /// Reads the sections, errors name the full path of the offending field
+    fn from_config(config: &Config) -> Result<Self, ConfigError> {
+        Ok(Configuration {
+            indexer_settings: get_section(config, "indexer_settings")?,
+            db_settings: get_section(config, "db_settings")?,
+            fetcher_settings: get_optional_section(config, "fetcher_settings")?,
+        })
+    }
+}
+
+/// Configuration values set in code, they take precedence over both the file and the environment
+#[derive(Clone, Debug, Default)]
+pub struct ConfigOverrides {
+    values: Vec<(String, Value)>,
+}
+
+impl ConfigOverrides {
+    /// Overrides the field at the dotted `key`, e.g. `indexer_settings.connection_str`
+    pub fn set(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.values.push((key.into(), value.into()));
+        self
+    }
+}
+
+/// Reads the configuration file with environment variable overrides applied
+pub fn get_configuration<'de, T: Deserialize<'de>>() -> IndexingResult<T> {
+    get_configuration_with_overrides(&ConfigOverrides::default())
+}
+
+/// Reads the configuration in layers, each one overriding the previous:
+/// the file named by `INDEXER_CFG`, `INDEXER__SECTION__FIELD` environment variables
+/// and finally the `overrides` set in code
+pub fn get_configuration_with_overrides<'de, T: Deserialize<'de>>(
+    overrides: &ConfigOverrides,
+) -> IndexingResult<T> {
+    layered_config(overrides)?
+        .try_deserialize::<T>()
+        .map_err(Into::into)
+}
+
+/// Returns the path of the configuration file
+pub(crate) fn config_path() -> String {
+    std::env::var("INDEXER_CFG").unwrap_or_else(|_| "configuration.yaml".to_string())
+}
+
+fn layered_config(overrides: &ConfigOverrides) -> Result<Config, ConfigError> {
+    build_config(&config_path(), std::env::vars().collect(), overrides)
+}
+
+/// Layers the file, the environment variables `env` and the overrides
+fn build_config(
+    path: &str,
+    env: Map<String, String>,
+    overrides: &ConfigOverrides,
+) -> Result<Config, ConfigError> {
+    let migrate = env.get("INDEXER_MIGRATE").cloned();
+
+    let mut builder = Config::builder()
+        .add_source(File::with_name(path).required(true))
+        .add_source(
+            Environment::with_prefix(ENV_PREFIX)
+                .prefix_separator(ENV_SEPARATOR)
+                .separator(ENV_SEPARATOR)
+                .source(Some(env)),
+        );
+    builder = legacy_overrides(builder, migrate)?;
+
+    for (key, value) in &overrides.values {
+        builder = builder.set_override(key.as_str(), value.clone())?;
+    }
+
+    builder.build()
+}
+
+/// Applies environment variables that predate the `INDEXER__SECTION__FIELD` scheme,
+/// `migrate` is the value of `INDEXER_MIGRATE`
+fn legacy_overrides<St: config::builder::BuilderState>(
+    builder: ConfigBuilder<St>,
+    migrate: Option<String>,
+) -> Result<ConfigBuilder<St>, ConfigError> {
+    match migrate {
+        Some(value) => builder.set_override(
+            "indexer_settings.migrate",
+            matches!(value.as_str(), "1" | "true" | "TRUE" | "y"),
+        ),
+        None => Ok(builder),
+    }
+}
+
+fn get_section<'de, T: Deserialize<'de>>(config: &Config, section: &str) -> Result<T, ConfigError> {
+    config
+        .get::<Value>(section)
+        .and_then(T::deserialize)
+        .map_err(|error| in_section(section, error))
+}
+
+fn get_optional_section<'de, T: Deserialize<'de>>(
+    config: &Config,
+    section: &str,
+) -> Result<Option<T>, ConfigError> {
+    match config.get::<Value>(section) {
+        Ok(value) => T::deserialize(value)
+            .map(Some)
+            .map_err(|error| in_section(section, error)),
+        Err(ConfigError::NotFound(key)) if key == section => Ok(None),
+        Err(error) => Err(in_section(section, error)),
+    }
+}
+
+/// Prefixes the key of a section error with the section name,
+/// so that the error names the full path of the offending field
+fn in_section(section: &str, error: ConfigError) -> ConfigError {
+    let with_section = |key: String| {
+        if key.is_empty() || key == section {
+            section.to_string()
+        } else if key.starts_with('[') {
+            format!("{section}{key}")
+        } else {
+            format!("{section}.{key}")
+        }
+    };
+
+    match error {
+        ConfigError::NotFound(key) => ConfigError::NotFound(with_section(key)),
+        ConfigError::Type {
+            origin,
+            unexpected,
+            expected,
+            key,
+        } => ConfigError::Type {
+            origin,
+            unexpected,
+            expected,
+            key: Some(with_section(key.unwrap_or_default())),
+        },
+        // serde reports missing fields without a path
+        ConfigError::Message(message) => match message
+            .strip_prefix("missing field `")
+            .and_then(|field| field.strip_suffix('`'))
+        {
+            Some(field) => ConfigError::NotFound(with_section(field.to_string())),
+            None => ConfigError::Message(format!("{message} for key `{section}`")),
+        },
+        error => error,
+    }
+}
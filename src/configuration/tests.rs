@@ -0,0 +1,262 @@
+use {
+    super::*,
+    crate::{
+        db::DatabaseSettings, fetcher::MAX_TRANSACTION_BATCH_SIZE, indexer::structural_changes,
+        BackfillSettings, FetchingSettings, FetchingStrategy, IndexerSettings, IndexingBound,
+        MethodRateLimit, OutboxSettings, RateLimitSettings, RpcEndpointSettings,
+    },
+    enum_extract::let_extract,
+    secrecy::{ExposeSecret, Secret},
+};
+
+fn get_configuration_stub() -> Configuration {
+    Configuration {
+        indexer_settings: IndexerSettings {
+            program_id: "hapiAwBQLYRXrjGn6FLCgC8FpQd2yWbKMqS6AYZ48g6".into(),
+            connection_str: "https://api.devnet.solana.com".into(),
+            ws_connection_str: None,
+            timestamp_interval: 5,
+            rpc_timeout: None,
+            migrate: None,
+            probe_on_start: None,
+            hot_reload: None,
+            log_level: None,
+            rpc_endpoints: None,
+            commitment: None,
+            start: None,
+            end: None,
+            processing_order: None,
+            supervisor: None,
+            leader_election: None,
+            backfill: None,
+            outbox: None,
+        },
+        db_settings: DatabaseSettings {
+            username: "postgres".into(),
+            password: Secret::new("postgres".into()),
+            port: 5432,
+            host: "localhost".into(),
+            database_name: "indexer".into(),
+            require_ssl: false,
+            ssl_root_cert: None,
+        },
+        fetcher_settings: Some(FetchingSettings::default()),
+    }
+}
+
+#[test]
+fn valid_configuration_test() {
+    let report = get_configuration_stub().validate();
+
+    assert!(report.is_valid(), "{report}");
+}
+
+#[test]
+fn invalid_configuration_test() {
+    let mut configuration = get_configuration_stub();
+    configuration.indexer_settings.program_id = "not a pubkey".into();
+    configuration.indexer_settings.connection_str = "localhost:8899".into();
+    configuration.indexer_settings.ws_connection_str = Some("https://api.devnet.solana.com".into());
+    configuration.indexer_settings.start = Some(IndexingBound::Slot(200));
+    configuration.indexer_settings.end = Some(IndexingBound::Slot(100));
+    configuration.indexer_settings.rpc_endpoints = Some(vec![RpcEndpointSettings {
+        url: "not a url".into(),
+        priority: None,
+        weight: Some(0),
+    }]);
+    configuration.db_settings.port = 0;
+
+    let fetcher_settings = configuration.fetcher_settings.as_mut().unwrap();
+    fetcher_settings.retry_limit = 0;
+    fetcher_settings.transaction_batch_size = MAX_TRANSACTION_BATCH_SIZE + 1;
+    fetcher_settings.rate_limits = Some(RateLimitSettings {
+        get_transaction: Some(MethodRateLimit {
+            requests_per_second: 0.0,
+            burst: None,
+        }),
+        ..Default::default()
+    });
+
+    let report = configuration.validate();
+
+    let fields = [
+        "indexer_settings.program_id",
+        "indexer_settings.connection_str",
+        "indexer_settings.ws_connection_str",
+        "indexer_settings.start",
+        "indexer_settings.rpc_endpoints[0].url",
+        "indexer_settings.rpc_endpoints[0].weight",
+        "db_settings.port",
+        "fetcher_settings.retry_limit",
+        "fetcher_settings.transaction_batch_size",
+        "fetcher_settings.rate_limits.get_transaction.requests_per_second",
+    ];
+    for field in fields {
+        assert!(report.has_issue(field), "{field} is not reported: {report}");
+    }
+    assert_eq!(report.issues().len(), fields.len(), "{report}");
+    assert!(report.into_result().is_err());
+}
+
+#[test]
+fn batch_size_limit_test() {
+    let mut settings = FetchingSettings::default();
+    assert!(validate_fetcher_settings(&settings).is_valid());
+
+    settings.transaction_batch_size = MAX_TRANSACTION_BATCH_SIZE;
+    assert!(validate_fetcher_settings(&settings).is_valid());
+
+    settings.transaction_batch_size = 0;
+    assert!(
+        validate_fetcher_settings(&settings).has_issue("fetcher_settings.transaction_batch_size")
+    );
+}
+
+#[test]
+fn block_bounds_test() {
+    let mut configuration = get_configuration_stub();
+    configuration.indexer_settings.start = Some(IndexingBound::Timestamp(1_700_000_000));
+    configuration.indexer_settings.end = Some(IndexingBound::Slot(100));
+    assert!(configuration.validate().is_valid());
+
+    configuration.fetcher_settings.as_mut().unwrap().strategy = FetchingStrategy::Blocks;
+    let report = configuration.validate();
+    assert!(report.has_issue("indexer_settings.start"), "{report}");
+    assert!(!report.has_issue("indexer_settings.end"), "{report}");
+}
+
+#[test]
+fn structural_changes_test() {
+    let current = get_configuration_stub();
+
+    let mut reloaded = current.clone();
+    reloaded.indexer_settings.timestamp_interval = 60;
+    reloaded.indexer_settings.log_level = Some("debug".into());
+    let fetcher_settings = reloaded.fetcher_settings.as_mut().unwrap();
+    fetcher_settings.retry_limit = 3;
+    fetcher_settings.transaction_batch_size = 100;
+    assert!(structural_changes(&current, &reloaded).is_empty());
+
+    reloaded.indexer_settings.program_id = "11111111111111111111111111111111".into();
+    reloaded.indexer_settings.backfill = Some(BackfillSettings::default());
+    reloaded.indexer_settings.outbox = Some(OutboxSettings::default());
+    reloaded.db_settings.password = Secret::new("changed".into());
+    assert_eq!(
+        structural_changes(&current, &reloaded),
+        vec![
+            "indexer_settings.program_id",
+            "indexer_settings.backfill",
+            "indexer_settings.outbox",
+            "db_settings"
+        ]
+    );
+}
+
+fn env(vars: &[(&str, &str)]) -> Map<String, String> {
+    vars.iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+fn load(path: &str, env: Map<String, String>, overrides: &ConfigOverrides) -> Configuration {
+    let config = build_config(path, env, overrides).expect("Failed to build configuration");
+    Configuration::from_config(&config).expect("Failed to read configuration")
+}
+
+#[test]
+fn env_overrides_test() {
+    let configuration = load(
+        "configuration.yaml",
+        env(&[
+            ("INDEXER__INDEXER_SETTINGS__TIMESTAMP_INTERVAL", "7"),
+            ("INDEXER__DB_SETTINGS__HOST", "db.internal"),
+            ("INDEXER__DB_SETTINGS__PASSWORD", "secret"),
+            ("UNRELATED__DB_SETTINGS__PORT", "1"),
+        ]),
+        &ConfigOverrides::default(),
+    );
+
+    assert_eq!(configuration.indexer_settings.timestamp_interval, 7);
+    assert_eq!(configuration.db_settings.host, "db.internal");
+    assert_eq!(configuration.db_settings.password.expose_secret(), "secret");
+    assert_eq!(configuration.db_settings.port, 5432);
+
+    // Overrides set in code take precedence over the environment
+    let configuration = load(
+        "configuration.yaml",
+        env(&[("INDEXER__INDEXER_SETTINGS__TIMESTAMP_INTERVAL", "7")]),
+        &ConfigOverrides::default().set("indexer_settings.timestamp_interval", 9),
+    );
+    assert_eq!(configuration.indexer_settings.timestamp_interval, 9);
+}
+
+#[test]
+fn legacy_migrate_override_test() {
+    for (value, migrate) in [
+        ("1", true),
+        ("true", true),
+        ("y", true),
+        ("0", false),
+        ("no", false),
+    ] {
+        let configuration = load(
+            "configuration.yaml",
+            env(&[
+                ("INDEXER_MIGRATE", value),
+                ("INDEXER__INDEXER_SETTINGS__MIGRATE", "false"),
+            ]),
+            &ConfigOverrides::default(),
+        );
+        assert_eq!(
+            configuration.indexer_settings.migrate,
+            Some(migrate),
+            "{value}"
+        );
+    }
+
+    let configuration = load(
+        "configuration.yaml",
+        env(&[("INDEXER_MIGRATE", "true")]),
+        &ConfigOverrides::default().set("indexer_settings.migrate", false),
+    );
+    assert_eq!(configuration.indexer_settings.migrate, Some(false));
+}
+
+#[test]
+fn section_error_path_test() {
+    let config = build_config(
+        "configuration.yaml",
+        env(&[("INDEXER__INDEXER_SETTINGS__TIMESTAMP_INTERVAL", "soon")]),
+        &ConfigOverrides::default(),
+    )
+    .expect("Failed to build configuration");
+    let_extract!(
+        Err(ConfigError::Type { key, .. }),
+        Configuration::from_config(&config),
+        panic!("Unexpected result")
+    );
+    assert_eq!(key.as_deref(), Some("indexer_settings.timestamp_interval"));
+
+    let path = std::env::temp_dir().join(format!("{}.yaml", uuid::Uuid::new_v4()));
+    std::fs::write(
+        &path,
+        std::fs::read_to_string("configuration.yaml")
+            .expect("Failed to read configuration")
+            .replace("  host: \"localhost\"\n", ""),
+    )
+    .expect("Failed to write configuration");
+    let config = build_config(
+        path.to_str().unwrap(),
+        Map::new(),
+        &ConfigOverrides::default(),
+    )
+    .expect("Failed to build configuration");
+    std::fs::remove_file(&path).expect("Failed to remove configuration");
+
+    let_extract!(
+        Err(ConfigError::NotFound(key)),
+        Configuration::from_config(&config),
+        panic!("Unexpected result")
+    );
+    assert_eq!(key, "db_settings.host");
+}
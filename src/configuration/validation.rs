@@ -0,0 +1,388 @@
+use {
+    super::Configuration,
+    crate::{
+        db::{DatabaseSettings, DbManager},
+        fetcher::{
+            FetchingSettings, FetchingStrategy, MethodRateLimit, MAX_TRANSACTION_BATCH_SIZE,
+        },
+        indexer::indexer_settings::{IndexerSettings, IndexingBound},
+    },
+    solana_client::nonblocking::rpc_client::RpcClient,
+    solana_sdk::{pubkey::Pubkey, signature::Signature},
+    std::{fmt, str::FromStr, time::Duration},
+    url::Url,
+};
+
+/// Time given to a single connectivity probe
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A problem of a single configuration field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    /// Dotted path of the field, e.g. `fetcher_settings.retry_limit`
+    pub field: String,
+
+    /// Description of the problem
+    pub message: String,
+}
+
+/// All problems found in a configuration
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// Whether no problems were found
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Returns the found problems
+    pub fn issues(&self) -> &[ValidationIssue] {
+        &self.issues
+    }
+
+    /// Whether a problem was found in the field
+    pub fn has_issue(&self, field: &str) -> bool {
+        self.issues.iter().any(|issue| issue.field == field)
+    }
+
+    /// Appends problems of another report
+    pub fn extend(&mut self, other: ValidationReport) {
+        self.issues.extend(other.issues);
+    }
+
+    /// Turns a report with problems into an error
+    pub fn into_result(self) -> Result<(), ValidationReport> {
+        if self.is_valid() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+
+    fn push(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.issues.push(ValidationIssue {
+            field: field.into(),
+            message: message.into(),
+        });
+    }
+
+    fn check(&mut self, condition: bool, field: impl Into<String>, message: impl Into<String>) {
+        if !condition {
+            self.push(field, message);
+        }
+    }
+
+    fn check_url(&mut self, field: String, url: &str, schemes: &[&str]) {
+        match Url::parse(url) {
+            Ok(url) if schemes.contains(&url.scheme()) => (),
+            Ok(url) => self.push(
+                field,
+                format!(
+                    "scheme `{}` is not supported, expected one of: {}",
+                    url.scheme(),
+                    schemes.join(", ")
+                ),
+            ),
+            Err(error) => self.push(field, format!("is not a valid URL: {error}")),
+        }
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid configuration:")?;
+        for issue in &self.issues {
+            write!(f, "\n  {}: {}", issue.field, issue.message)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationReport {}
+
+impl Configuration {
+    /// Checks ranges and formats of all fields, the returned report lists every problem found
+    pub fn validate(&self) -> ValidationReport {
+        let mut report = validate_indexer_settings(&self.indexer_settings);
+        report.extend(validate_db_settings(&self.db_settings));
+        if let Some(fetcher_settings) = &self.fetcher_settings {
+            report.extend(validate_fetcher_settings(fetcher_settings));
+            if fetcher_settings.strategy == FetchingStrategy::Blocks {
+                report.extend(validate_block_bounds(&self.indexer_settings));
+            }
+        }
+        report
+    }
+
+    /// Checks that the configured RPC endpoints and database are reachable
+    pub async fn probe(&self) -> ValidationReport {
+        let mut report = probe_rpc(&self.indexer_settings).await;
+        report.extend(probe_db(&self.db_settings).await);
+        report
+    }
+}
+
+/// Checks the general indexer configuration
+pub fn validate_indexer_settings(settings: &IndexerSettings) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    report.check(
+        Pubkey::from_str(&settings.program_id).is_ok(),
+        "indexer_settings.program_id",
+        "is not a valid public key",
+    );
+    report.check_url(
+        "indexer_settings.connection_str".to_string(),
+        &settings.connection_str,
+        &["http", "https"],
+    );
+    if let Some(ws_connection_str) = &settings.ws_connection_str {
+        report.check_url(
+            "indexer_settings.ws_connection_str".to_string(),
+            ws_connection_str,
+            &["ws", "wss"],
+        );
+    }
+    report.check(
+        settings.timestamp_interval >= 0,
+        "indexer_settings.timestamp_interval",
+        "must not be negative",
+    );
+    report.check(
+        settings.rpc_timeout != Some(0),
+        "indexer_settings.rpc_timeout",
+        "must be positive",
+    );
+
+    for (index, endpoint) in settings.rpc_endpoints.iter().flatten().enumerate() {
+        report.check_url(
+            format!("indexer_settings.rpc_endpoints[{index}].url"),
+            &endpoint.url,
+            &["http", "https"],
+        );
+        report.check(
+            endpoint.weight != Some(0),
+            format!("indexer_settings.rpc_endpoints[{index}].weight"),
+            "must be positive",
+        );
+    }
+
+    for (field, bound) in [("start", &settings.start), ("end", &settings.end)] {
+        if let Some(IndexingBound::Signature(signature)) = bound {
+            report.check(
+                Signature::from_str(signature).is_ok(),
+                format!("indexer_settings.{field}.signature"),
+                "is not a valid transaction signature",
+            );
+        }
+    }
+    match (&settings.start, &settings.end) {
+        (Some(IndexingBound::Slot(start)), Some(IndexingBound::Slot(end))) => {
+            report.check(start <= end, "indexer_settings.start", "is after `end`")
+        }
+        (Some(IndexingBound::Timestamp(start)), Some(IndexingBound::Timestamp(end))) => {
+            report.check(start <= end, "indexer_settings.start", "is after `end`")
+        }
+        _ => (),
+    }
+
+    if let Some(supervisor) = &settings.supervisor {
+        report.check(
+            supervisor.restart_delay > 0,
+            "indexer_settings.supervisor.restart_delay",
+            "must be positive",
+        );
+        report.check(
+            supervisor.max_restart_delay >= supervisor.restart_delay,
+            "indexer_settings.supervisor.max_restart_delay",
+            "must not be less than `restart_delay`",
+        );
+        report.check(
+            supervisor.restart_window > 0,
+            "indexer_settings.supervisor.restart_window",
+            "must be positive",
+        );
+    }
+
+    if let Some(leader_election) = &settings.leader_election {
+        report.check(
+            leader_election.renew_interval > 0,
+            "indexer_settings.leader_election.renew_interval",
+            "must be positive",
+        );
+        report.check(
+            leader_election.lease_ttl > leader_election.renew_interval,
+            "indexer_settings.leader_election.lease_ttl",
+            "must be greater than `renew_interval`",
+        );
+    }
+
+    if let Some(backfill) = &settings.backfill {
+        report.check(
+            backfill.unit_size > 0,
+            "indexer_settings.backfill.unit_size",
+            "must be positive",
+        );
+        report.check(
+            backfill.claim_timeout > 0,
+            "indexer_settings.backfill.claim_timeout",
+            "must be positive",
+        );
+    }
+
+    if let Some(outbox) = &settings.outbox {
+        report.check(
+            outbox.batch_size > 0,
+            "indexer_settings.outbox.batch_size",
+            "must be positive",
+        );
+    }
+
+    report
+}
+
+/// Checks that the indexed range is bounded by slots, the only bounds
+/// the block-based ingestion can walk through
+fn validate_block_bounds(settings: &IndexerSettings) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    for (field, bound) in [("start", &settings.start), ("end", &settings.end)] {
+        report.check(
+            bound.as_ref().map_or(true, |bound| bound.slot().is_some()),
+            format!("indexer_settings.{field}"),
+            "must be a slot with the `blocks` fetching strategy",
+        );
+    }
+
+    report
+}
+
+/// Checks the configuration of the fetching process
+pub fn validate_fetcher_settings(settings: &FetchingSettings) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    report.check(
+        settings.rpc_request_timeout > 0,
+        "fetcher_settings.rpc_request_timeout",
+        "must be positive",
+    );
+    report.check(
+        settings.retry_limit > 0,
+        "fetcher_settings.retry_limit",
+        "must be positive",
+    );
+    report.check(
+        (1..=MAX_TRANSACTION_BATCH_SIZE).contains(&settings.transaction_batch_size),
+        "fetcher_settings.transaction_batch_size",
+        format!("must be between 1 and {MAX_TRANSACTION_BATCH_SIZE}"),
+    );
+
+    if let Some(rate_limits) = &settings.rate_limits {
+        let limits = [
+            (
+                "get_signatures_for_address",
+                &rate_limits.get_signatures_for_address,
+            ),
+            ("get_transaction", &rate_limits.get_transaction),
+            ("get_block", &rate_limits.get_block),
+        ];
+        for (method, limit) in limits
+            .into_iter()
+            .filter_map(|(method, limit)| Some((method, limit.as_ref()?)))
+        {
+            validate_rate_limit(&mut report, method, limit);
+        }
+    }
+
+    report
+}
+
+fn validate_rate_limit(report: &mut ValidationReport, method: &str, limit: &MethodRateLimit) {
+    report.check(
+        limit.requests_per_second.is_finite() && limit.requests_per_second > 0.0,
+        format!("fetcher_settings.rate_limits.{method}.requests_per_second"),
+        "must be positive",
+    );
+    report.check(
+        limit.burst != Some(0),
+        format!("fetcher_settings.rate_limits.{method}.burst"),
+        "must be positive",
+    );
+}
+
+/// Checks the database configuration
+pub fn validate_db_settings(settings: &DatabaseSettings) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    report.check(
+        !settings.host.is_empty(),
+        "db_settings.host",
+        "must not be empty",
+    );
+    report.check(settings.port > 0, "db_settings.port", "must be positive");
+    report.check(
+        !settings.username.is_empty(),
+        "db_settings.username",
+        "must not be empty",
+    );
+    report.check(
+        !settings.database_name.is_empty(),
+        "db_settings.database_name",
+        "must not be empty",
+    );
+    if let Some(ssl_root_cert) = &settings.ssl_root_cert {
+        report.check(
+            std::path::Path::new(ssl_root_cert).is_file(),
+            "db_settings.ssl_root_cert",
+            "file does not exist",
+        );
+    }
+
+    report
+}
+
+async fn probe_rpc(settings: &IndexerSettings) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    let endpoints =
+        std::iter::once((
+            "indexer_settings.connection_str".to_string(),
+            &settings.connection_str,
+        ))
+        .chain(settings.rpc_endpoints.iter().flatten().enumerate().map(
+            |(index, endpoint)| {
+                (
+                    format!("indexer_settings.rpc_endpoints[{index}].url"),
+                    &endpoint.url,
+                )
+            },
+        ));
+
+    for (field, url) in endpoints {
+        let client = RpcClient::new_with_timeout(url.clone(), PROBE_TIMEOUT);
+        if let Err(error) = client.get_version().await {
+            report.push(field, format!("RPC node is unreachable: {error}"));
+        }
+    }
+
+    report
+}
+
+async fn probe_db(settings: &DatabaseSettings) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    let result = match DbManager::connect(settings.with_db()) {
+        Ok(db_manager) => tokio::time::timeout(PROBE_TIMEOUT, db_manager.ping())
+            .await
+            .unwrap_or_else(|_| Err(sqlx::Error::PoolTimedOut)),
+        Err(error) => Err(error),
+    };
+    if let Err(error) = result {
+        report.push("db_settings", format!("database is unreachable: {error}"));
+    }
+
+    report
+}
@@ -1,10 +1,22 @@
-use sqlx::{
-    migrate::{MigrateError, Migrator},
-    postgres::{PgConnectOptions, PgPool, PgPoolOptions},
-    Error, Row,
+use {
+    chrono::Utc,
+    futures::{stream::BoxStream, StreamExt},
+    solana_sdk::clock::{Slot, UnixTimestamp},
+    sqlx::{
+        migrate::{MigrateError, Migrator},
+        postgres::{PgConnectOptions, PgPool, PgPoolOptions},
+        Error, Row,
+    },
+    tracing::warn,
 };
 
-use crate::{fetcher::Tx, processor::Instruction};
+use crate::{
+    archive::{ArchiveRange, InstructionRecord, TransactionRecord},
+    executor::TxSignature,
+    fetcher::{Tx, TxBatch, TxCommitment},
+    indexer::{ErrorCategory, ErrorContext, IndexerError, LeaseGuard},
+    processor::{Instruction, Outbox},
+};
 
 static MIGRATOR: Migrator = sqlx::migrate!();
 
@@ -15,6 +27,141 @@ pub struct DbManager {
     pool: PgPool,
 }
 
+/// A durable position of an indexing process
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cursor {
+    /// Last processed slot
+    pub slot: Option<Slot>,
+
+    /// Last processed transaction signature
+    pub signature: Option<String>,
+}
+
+/// A compressed transaction as it was fetched from RPC node
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawTxRecord {
+    /// Transaction signature hash
+    pub hash: String,
+
+    /// Slot of transaction block
+    pub slot: Slot,
+
+    /// Gzip compressed JSON of the transaction
+    pub data: Vec<u8>,
+}
+
+/// An error that stopped the indexing process
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorRecord {
+    /// Error description
+    pub message: String,
+
+    /// Time when the error occurred
+    pub occurred_at: UnixTimestamp,
+}
+
+/// State of a backfill unit
+#[derive(sqlx::Type, Debug, Clone, Copy, PartialEq, Eq)]
+#[sqlx(type_name = "backfill_status", rename_all = "lowercase")]
+pub enum BackfillStatus {
+    /// Waiting for a worker
+    Pending,
+    /// Indexed by a worker
+    Claimed,
+    /// Indexed completely
+    Completed,
+}
+
+/// A slot range of history indexed by a single backfill worker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackfillUnit {
+    pub id: i64,
+
+    /// The first slot of the range
+    pub start_slot: Slot,
+
+    /// The last slot of the range
+    pub end_slot: Slot,
+
+    pub status: BackfillStatus,
+
+    /// Identity of the worker that claimed the unit
+    pub worker: Option<String>,
+}
+
+/// Amount of backfill units by their state
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BackfillProgress {
+    pub pending: i64,
+    pub claimed: i64,
+    pub completed: i64,
+}
+
+/// A side effect queued by a callback
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutboxRecord {
+    pub id: i64,
+
+    /// Key the receiver deduplicates the message by
+    pub idempotency_key: String,
+
+    /// Destination of the message
+    pub topic: String,
+
+    /// Serialized message
+    pub payload: String,
+
+    /// Amount of failed deliveries
+    pub attempts: i32,
+
+    /// Description of the last failed delivery
+    pub last_error: Option<String>,
+}
+
+/// A single request to a webhook
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebhookDeliveryRecord {
+    /// URL of the webhook
+    pub url: String,
+
+    /// Idempotency keys of the delivered events
+    pub keys: Vec<String>,
+
+    /// Number of the attempt, starting with 1
+    pub attempt: i32,
+
+    /// HTTP status of the response, absent if no response was received
+    pub status: Option<i16>,
+
+    /// Description of the failure, absent if the events were delivered
+    pub error: Option<String>,
+
+    /// Time of the request
+    pub occurred_at: UnixTimestamp,
+}
+
+/// A transaction or instruction that was skipped because of a data error
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeadLetterRecord {
+    /// Signature of the transaction
+    pub signature: Option<String>,
+
+    /// Slot of the transaction block
+    pub slot: Option<Slot>,
+
+    /// Index of the instruction within the transaction
+    pub instruction: Option<u8>,
+
+    /// Category of the error
+    pub category: String,
+
+    /// Error description
+    pub message: String,
+
+    /// Time when the error occurred
+    pub occurred_at: UnixTimestamp,
+}
+
 pub trait IndexerDbRecording {
     async fn insert_transaction(&self, tx: &Tx) -> DbResult<()>;
     async fn update_transaction(&self, tx: &Tx) -> DbResult<()>;
@@ -22,6 +169,69 @@ pub trait IndexerDbRecording {
     async fn get_most_recent_tx(&self) -> DbResult<Option<String>>;
     async fn recorded_tx(&self, signature: &str) -> DbResult<bool>;
     async fn recorded_instruction(&self, instruction: &Instruction) -> DbResult<bool>;
+    async fn get_cursor(&self, name: &str) -> DbResult<Option<Cursor>>;
+    async fn set_cursor(&self, name: &str, cursor: &Cursor) -> DbResult<()>;
+    async fn delete_cursor(&self, name: &str) -> DbResult<()>;
+    async fn insert_pending_signatures(&self, scope: &str, signatures: &TxBatch) -> DbResult<()>;
+    async fn get_pending_signatures(&self, scope: &str, limit: i64) -> DbResult<TxBatch>;
+    async fn delete_pending_signatures(&self, scope: &str, signatures: &TxBatch) -> DbResult<()>;
+    async fn clear_pending_signatures(&self, scope: &str) -> DbResult<()>;
+    async fn get_confirmed_txs(&self, limit: i64) -> DbResult<Vec<(String, Slot)>>;
+    async fn insert_raw_transaction(&self, record: &RawTxRecord) -> DbResult<()>;
+    async fn get_raw_transactions(
+        &self,
+        start_slot: Slot,
+        end_slot: Slot,
+        after: Option<&RawTxRecord>,
+        limit: i64,
+    ) -> DbResult<Vec<RawTxRecord>>;
+    async fn delete_instructions(&self, tx_hashes: &[String]) -> DbResult<()>;
+    async fn delete_recorded_instructions(&self, keys: &[String]) -> DbResult<()>;
+    async fn update_commitment(&self, hash: &str, commitment: TxCommitment) -> DbResult<()>;
+    async fn get_pending_txs(&self) -> DbResult<TxBatch>;
+    async fn count_pending_txs(&self) -> DbResult<i64>;
+    async fn count_pending_signatures(&self) -> DbResult<i64>;
+    async fn insert_error(&self, message: &str) -> DbResult<()>;
+    async fn get_last_error(&self) -> DbResult<Option<ErrorRecord>>;
+    async fn insert_dead_letter(
+        &self,
+        context: &ErrorContext,
+        category: ErrorCategory,
+        message: &str,
+    ) -> DbResult<()>;
+    async fn get_dead_letters(&self, limit: i64) -> DbResult<Vec<DeadLetterRecord>>;
+    async fn try_acquire_lease(&self, name: &str, holder: &str, ttl: i64) -> DbResult<bool>;
+    async fn release_lease(&self, name: &str, holder: &str) -> DbResult<()>;
+    async fn holds_lease(&self, name: &str, holder: &str) -> DbResult<bool>;
+    async fn insert_backfill_units(&self, ranges: &[(Slot, Slot)]) -> DbResult<u64>;
+    async fn claim_backfill_unit(
+        &self,
+        worker: &str,
+        claim_timeout: i64,
+    ) -> DbResult<Option<BackfillUnit>>;
+    async fn update_backfill_unit(
+        &self,
+        id: i64,
+        worker: &str,
+        status: BackfillStatus,
+    ) -> DbResult<bool>;
+    async fn get_backfill_progress(&self) -> DbResult<BackfillProgress>;
+    async fn insert_instruction_with_outbox(
+        &self,
+        instruction: &Instruction,
+        outbox: &Outbox,
+        lease: Option<&LeaseGuard>,
+    ) -> DbResult<bool>;
+    async fn get_undelivered_messages(
+        &self,
+        topic: Option<&str>,
+        limit: i64,
+    ) -> DbResult<Vec<OutboxRecord>>;
+    async fn mark_delivered(&self, id: i64) -> DbResult<()>;
+    async fn record_delivery_failure(&self, id: i64, error: &str) -> DbResult<()>;
+    async fn dead_letter_message(&self, id: i64, error: &str) -> DbResult<()>;
+    async fn insert_webhook_delivery(&self, record: &WebhookDeliveryRecord) -> DbResult<()>;
+    async fn get_webhook_deliveries(&self, limit: i64) -> DbResult<Vec<WebhookDeliveryRecord>>;
 }
 
 impl DbManager {
@@ -35,10 +245,154 @@ impl DbManager {
         Ok(Self { pool })
     }
 
+    /// Checks that the database accepts queries
+    pub async fn ping(&self) -> DbResult<()> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+
+        Ok(())
+    }
+
+    /// Stores an error that made the indexer skip a transaction
+    pub async fn dead_letter(&self, error: &IndexerError) -> DbResult<()> {
+        let context = error.context().cloned().unwrap_or_default();
+        warn!(
+            signature = context.signature,
+            slot = context.slot,
+            instruction = context.instruction,
+            error = %error.root(),
+            "Transaction skipped because of a data error"
+        );
+
+        self.insert_dead_letter(&context, error.category(), &error.root().to_string())
+            .await
+    }
+
+    /// Wraps a pool of connections created by the caller
+    pub fn from_pool(pool: PgPool) -> DbManager {
+        Self { pool }
+    }
+
     pub async fn migrate(&self) -> Result<(), MigrateError> {
         MIGRATOR.run(&self.pool).await?;
         Ok(())
     }
+
+    /// Streams transactions of the range together with their compressed raw form
+    pub fn stream_transaction_records(
+        &self,
+        range: ArchiveRange,
+    ) -> BoxStream<'_, DbResult<(TransactionRecord, Option<Vec<u8>>)>> {
+        let (slots, timestamps) = range_bounds(range);
+
+        sqlx::query(
+            "SELECT t.hash, t.slot, t.blocktime, t.indexing_status::TEXT AS indexing_status, \
+                t.commitment::TEXT AS commitment, t.indexing_timestamp, r.data \
+                FROM transactions t LEFT JOIN raw_transactions r ON r.hash = t.hash \
+                WHERE ($1::BIGINT IS NULL OR t.slot BETWEEN $1 AND $2) \
+                AND ($3::BIGINT IS NULL OR t.blocktime BETWEEN $3 AND $4) \
+                ORDER BY t.slot, t.blocktime, t.hash",
+        )
+        .bind(slots.0)
+        .bind(slots.1)
+        .bind(timestamps.0)
+        .bind(timestamps.1)
+        .fetch(&self.pool)
+        .map(|row| {
+            row.map(|row| {
+                let record = TransactionRecord {
+                    hash: row.get("hash"),
+                    slot: row.get::<Option<i64>, _>("slot").map(|slot| slot as Slot),
+                    blocktime: row.get("blocktime"),
+                    indexing_status: row.get("indexing_status"),
+                    commitment: row.get("commitment"),
+                    indexing_timestamp: row.get("indexing_timestamp"),
+                    raw: None,
+                };
+                (record, row.get::<Option<Vec<u8>>, _>("data"))
+            })
+        })
+        .boxed()
+    }
+
+    /// Streams processed instructions of transactions of the range
+    pub fn stream_instruction_records(
+        &self,
+        range: ArchiveRange,
+    ) -> BoxStream<'_, DbResult<InstructionRecord>> {
+        let (slots, timestamps) = range_bounds(range);
+
+        sqlx::query(
+            "SELECT i.id, i.tx_hash, i.program_id, i.blocktime, i.data \
+                FROM instructions i JOIN transactions t ON t.hash = i.tx_hash \
+                WHERE ($1::BIGINT IS NULL OR t.slot BETWEEN $1 AND $2) \
+                AND ($3::BIGINT IS NULL OR t.blocktime BETWEEN $3 AND $4) \
+                ORDER BY t.slot, t.blocktime, i.id",
+        )
+        .bind(slots.0)
+        .bind(slots.1)
+        .bind(timestamps.0)
+        .bind(timestamps.1)
+        .fetch(&self.pool)
+        .map(|row| {
+            row.map(|row| InstructionRecord {
+                id: row.get("id"),
+                tx_hash: row.get("tx_hash"),
+                program_id: row.get("program_id"),
+                blocktime: row.get("blocktime"),
+                data: row.get("data"),
+            })
+        })
+        .boxed()
+    }
+
+    /// Inserts archived transaction if it is absent
+    #[tracing::instrument(level = "debug", skip(self, record), fields(hash = %record.hash))]
+    pub async fn insert_transaction_record(&self, record: &TransactionRecord) -> DbResult<()> {
+        sqlx::query(
+            "INSERT INTO transactions \
+                (hash, slot, blocktime, indexing_status, commitment, indexing_timestamp) \
+                VALUES ($1, $2, $3, $4::tx_status, $5::tx_commitment, $6) \
+                ON CONFLICT (hash) DO NOTHING;",
+        )
+        .bind(&record.hash)
+        .bind(record.slot.map(|slot| slot as i64))
+        .bind(record.blocktime)
+        .bind(&record.indexing_status)
+        .bind(&record.commitment)
+        .bind(record.indexing_timestamp)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Inserts archived instruction if it is absent
+    #[tracing::instrument(level = "debug", skip(self, record), fields(id = %record.id))]
+    pub async fn insert_instruction_record(&self, record: &InstructionRecord) -> DbResult<()> {
+        sqlx::query(
+            "INSERT INTO instructions (id, tx_hash, program_id, blocktime, data) \
+                VALUES ($1, $2, $3, $4, $5) ON CONFLICT (id) DO NOTHING;",
+        )
+        .bind(&record.id)
+        .bind(&record.tx_hash)
+        .bind(&record.program_id)
+        .bind(record.blocktime)
+        .bind(&record.data)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Splits archive range into optional slot and blocktime bounds of queries
+fn range_bounds(range: ArchiveRange) -> ((Option<i64>, Option<i64>), (Option<i64>, Option<i64>)) {
+    match range {
+        ArchiveRange::Slots { start, end } => {
+            ((Some(start as i64), Some(end as i64)), (None, None))
+        }
+        ArchiveRange::Timestamps { start, end } => ((None, None), (Some(start), Some(end))),
+    }
 }
 
 impl IndexerDbRecording for DbManager {
@@ -46,13 +400,16 @@ impl IndexerDbRecording for DbManager {
     #[tracing::instrument(level = "debug", skip(self))]
     async fn insert_transaction(&self, tx: &Tx) -> DbResult<()> {
         sqlx::query(
-            "INSERT INTO transactions (hash, blocktime, indexing_status, indexing_timestamp) \
-                VALUES ($1, $2, $3, $4) ON CONFLICT (hash) DO NOTHING;",
+            "INSERT INTO transactions \
+                (hash, slot, blocktime, indexing_status, indexing_timestamp, commitment) \
+                VALUES ($1, $2, $3, $4, $5, $6) ON CONFLICT (hash) DO NOTHING;",
         )
         .bind(&tx.hash)
+        .bind(tx.slot as i64)
         .bind(tx.blocktime)
         .bind(&tx.indexing_status)
         .bind(tx.indexing_timestamp)
+        .bind(tx.commitment)
         .execute(&self.pool)
         .await?;
 
@@ -74,7 +431,7 @@ impl IndexerDbRecording for DbManager {
     /// Inserts instruction entity to db
     #[tracing::instrument(level = "debug", skip(self))]
     async fn insert_instruction(&self, instruction: &Instruction) -> DbResult<()> {
-        let id = instruction.tx_hash.clone() + instruction.id.to_string().as_str();
+        let id = instruction.idempotency_key();
 
         sqlx::query(
             "INSERT INTO instructions (id, tx_hash, program_id, blocktime, data) \
@@ -120,7 +477,7 @@ impl IndexerDbRecording for DbManager {
     /// Checks if instruction is processed
     #[tracing::instrument(level = "trace", skip(self))]
     async fn recorded_instruction(&self, instruction: &Instruction) -> DbResult<bool> {
-        let id = instruction.tx_hash.clone() + instruction.id.to_string().as_str();
+        let id = instruction.idempotency_key();
 
         let instruction = sqlx::query("SELECT FROM instructions WHERE id = $1")
             .bind(&id)
@@ -129,4 +486,672 @@ impl IndexerDbRecording for DbManager {
 
         Ok(!instruction.is_empty())
     }
+
+    /// Returns stored position of the indexing process
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn get_cursor(&self, name: &str) -> DbResult<Option<Cursor>> {
+        let row = sqlx::query("SELECT slot, signature FROM cursors WHERE name = $1")
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|row| Cursor {
+            slot: row.get::<Option<i64>, _>("slot").map(|slot| slot as Slot),
+            signature: row.get("signature"),
+        }))
+    }
+
+    /// Stores position of the indexing process
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn set_cursor(&self, name: &str, cursor: &Cursor) -> DbResult<()> {
+        sqlx::query(
+            "INSERT INTO cursors (name, slot, signature, updated_at) VALUES ($1, $2, $3, $4) \
+                ON CONFLICT (name) DO UPDATE SET slot = EXCLUDED.slot, \
+                signature = EXCLUDED.signature, updated_at = EXCLUDED.updated_at;",
+        )
+        .bind(name)
+        .bind(cursor.slot.map(|slot| slot as i64))
+        .bind(&cursor.signature)
+        .bind(Utc::now().timestamp())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Removes position of the indexing process
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn delete_cursor(&self, name: &str) -> DbResult<()> {
+        sqlx::query("DELETE FROM cursors WHERE name = $1;")
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Stores signatures collected for the scope until they are processed
+    #[tracing::instrument(level = "debug", skip(self, signatures), fields(count = %signatures.len()))]
+    async fn insert_pending_signatures(&self, scope: &str, signatures: &TxBatch) -> DbResult<()> {
+        let mut transaction = self.pool.begin().await?;
+
+        for signature in signatures {
+            let status =
+                serde_json::to_string(signature).expect("Signature status is serializable");
+
+            sqlx::query(
+                "INSERT INTO pending_signatures (scope, signature, slot, status) \
+                    VALUES ($1, $2, $3, $4) ON CONFLICT (scope, signature) DO NOTHING;",
+            )
+            .bind(scope)
+            .bind(&signature.signature)
+            .bind(signature.slot as i64)
+            .bind(status)
+            .execute(&mut *transaction)
+            .await?;
+        }
+
+        transaction.commit().await
+    }
+
+    /// Returns the oldest pending signatures of the scope in the order of their appearance on the chain
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn get_pending_signatures(&self, scope: &str, limit: i64) -> DbResult<TxBatch> {
+        // Signatures of a slot are collected from the newest to the oldest one
+        let rows = sqlx::query(
+            "SELECT status FROM pending_signatures WHERE scope = $1 \
+                ORDER BY slot, id DESC LIMIT $2",
+        )
+        .bind(scope)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                serde_json::from_str::<TxSignature>(row.get("status"))
+                    .map_err(|err| Error::Decode(Box::new(err)))
+            })
+            .collect()
+    }
+
+    /// Removes processed signatures of the scope
+    #[tracing::instrument(level = "debug", skip(self, signatures), fields(count = %signatures.len()))]
+    async fn delete_pending_signatures(&self, scope: &str, signatures: &TxBatch) -> DbResult<()> {
+        let signatures = signatures
+            .iter()
+            .map(|signature| signature.signature.clone())
+            .collect::<Vec<_>>();
+
+        sqlx::query("DELETE FROM pending_signatures WHERE scope = $1 AND signature = ANY($2);")
+            .bind(scope)
+            .bind(signatures)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Removes all pending signatures of the scope
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn clear_pending_signatures(&self, scope: &str) -> DbResult<()> {
+        sqlx::query("DELETE FROM pending_signatures WHERE scope = $1;")
+            .bind(scope)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Stores compressed transaction for further reindexing
+    #[tracing::instrument(level = "debug", skip(self, record), fields(hash = %record.hash))]
+    async fn insert_raw_transaction(&self, record: &RawTxRecord) -> DbResult<()> {
+        sqlx::query(
+            "INSERT INTO raw_transactions (hash, slot, data) VALUES ($1, $2, $3) \
+                ON CONFLICT (hash) DO NOTHING;",
+        )
+        .bind(&record.hash)
+        .bind(record.slot as i64)
+        .bind(&record.data)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Returns stored transactions of the slot range, both inclusive, following the given one
+    #[tracing::instrument(level = "trace", skip(self, after))]
+    async fn get_raw_transactions(
+        &self,
+        start_slot: Slot,
+        end_slot: Slot,
+        after: Option<&RawTxRecord>,
+        limit: i64,
+    ) -> DbResult<Vec<RawTxRecord>> {
+        let (after_slot, after_hash) = match after {
+            Some(record) => (record.slot as i64, record.hash.as_str()),
+            None => (start_slot as i64 - 1, ""),
+        };
+
+        let rows = sqlx::query(
+            "SELECT hash, slot, data FROM raw_transactions \
+                WHERE slot >= $1 AND slot <= $2 AND (slot, hash) > ($3, $4) \
+                ORDER BY slot, hash LIMIT $5",
+        )
+        .bind(start_slot as i64)
+        .bind(end_slot as i64)
+        .bind(after_slot)
+        .bind(after_hash)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| RawTxRecord {
+                hash: row.get("hash"),
+                slot: row.get::<i64, _>("slot") as Slot,
+                data: row.get("data"),
+            })
+            .collect())
+    }
+
+    /// Removes processed instructions of transactions, so they can be processed again
+    #[tracing::instrument(level = "debug", skip(self, tx_hashes), fields(count = %tx_hashes.len()))]
+    async fn delete_instructions(&self, tx_hashes: &[String]) -> DbResult<()> {
+        sqlx::query("DELETE FROM instructions WHERE tx_hash = ANY($1);")
+            .bind(tx_hashes)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Removes instructions recorded under the idempotency keys together with their outbox messages
+    #[tracing::instrument(level = "debug", skip(self, keys), fields(count = keys.len()))]
+    async fn delete_recorded_instructions(&self, keys: &[String]) -> DbResult<()> {
+        let mut transaction = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM instructions WHERE id = ANY($1);")
+            .bind(keys)
+            .execute(&mut *transaction)
+            .await?;
+        // Keys of messages are made of the instruction key and the message index
+        sqlx::query(
+            "DELETE FROM outbox WHERE regexp_replace(idempotency_key, ':[0-9]+$', '') = ANY($1);",
+        )
+        .bind(keys)
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await
+    }
+
+    /// Returns the oldest transactions that are not finalized yet
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn get_confirmed_txs(&self, limit: i64) -> DbResult<Vec<(String, Slot)>> {
+        let rows = sqlx::query(
+            "SELECT hash, slot FROM transactions \
+                WHERE commitment = 'confirmed' AND slot IS NOT NULL ORDER BY slot LIMIT $1",
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| (row.get("hash"), row.get::<i64, _>("slot") as Slot))
+            .collect())
+    }
+
+    /// Updates commitment level of transaction
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn update_commitment(&self, hash: &str, commitment: TxCommitment) -> DbResult<()> {
+        sqlx::query("UPDATE transactions SET commitment = $1 WHERE hash = $2;")
+            .bind(commitment)
+            .bind(hash)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns transactions that were fetched but not processed
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn get_pending_txs(&self) -> DbResult<TxBatch> {
+        let rows = sqlx::query(
+            "SELECT hash, slot, blocktime FROM transactions \
+                WHERE indexing_status = 'pending' ORDER BY slot, blocktime",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| TxSignature {
+                signature: row.get("hash"),
+                slot: row.get::<Option<i64>, _>("slot").unwrap_or_default() as Slot,
+                err: None,
+                memo: None,
+                block_time: Some(row.get("blocktime")),
+                confirmation_status: None,
+            })
+            .collect())
+    }
+
+    /// Returns amount of transactions that were fetched but not processed
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn count_pending_txs(&self) -> DbResult<i64> {
+        sqlx::query("SELECT COUNT(*) AS count FROM transactions WHERE indexing_status = 'pending'")
+            .fetch_one(&self.pool)
+            .await
+            .map(|row| row.get("count"))
+    }
+
+    /// Returns amount of collected signatures waiting for processing
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn count_pending_signatures(&self) -> DbResult<i64> {
+        sqlx::query("SELECT COUNT(*) AS count FROM pending_signatures")
+            .fetch_one(&self.pool)
+            .await
+            .map(|row| row.get("count"))
+    }
+
+    /// Stores an error that stopped the indexing process
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn insert_error(&self, message: &str) -> DbResult<()> {
+        sqlx::query("INSERT INTO indexer_errors (message, occurred_at) VALUES ($1, $2);")
+            .bind(message)
+            .bind(Utc::now().timestamp())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns the latest error that stopped the indexing process
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn get_last_error(&self) -> DbResult<Option<ErrorRecord>> {
+        let row =
+            sqlx::query("SELECT message, occurred_at FROM indexer_errors ORDER BY id DESC LIMIT 1")
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(row.map(|row| ErrorRecord {
+            message: row.get("message"),
+            occurred_at: row.get("occurred_at"),
+        }))
+    }
+
+    /// Stores an error that made the indexer skip a transaction or instruction
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn insert_dead_letter(
+        &self,
+        context: &ErrorContext,
+        category: ErrorCategory,
+        message: &str,
+    ) -> DbResult<()> {
+        sqlx::query(
+            "INSERT INTO dead_letters (signature, slot, instruction, category, message, occurred_at) \
+                VALUES ($1, $2, $3, $4, $5, $6);",
+        )
+        .bind(&context.signature)
+        .bind(context.slot.map(|slot| slot as i64))
+        .bind(context.instruction.map(i16::from))
+        .bind(category.to_string())
+        .bind(message)
+        .bind(Utc::now().timestamp())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Returns the latest skipped transactions and instructions, the newest first
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn get_dead_letters(&self, limit: i64) -> DbResult<Vec<DeadLetterRecord>> {
+        let rows = sqlx::query(
+            "SELECT signature, slot, instruction, category, message, occurred_at \
+                FROM dead_letters ORDER BY id DESC LIMIT $1",
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| DeadLetterRecord {
+                signature: row.get("signature"),
+                slot: row.get::<Option<i64>, _>("slot").map(|slot| slot as Slot),
+                instruction: row
+                    .get::<Option<i16>, _>("instruction")
+                    .map(|instruction| instruction as u8),
+                category: row.get("category"),
+                message: row.get("message"),
+                occurred_at: row.get("occurred_at"),
+            })
+            .collect())
+    }
+
+    /// Acquires or renews the lease for `ttl` seconds if it is free, expired or held by the holder.
+    /// Returns whether the holder owns the lease.
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn try_acquire_lease(&self, name: &str, holder: &str, ttl: i64) -> DbResult<bool> {
+        // Expiration is measured by the database clock, replicas' clocks may drift apart
+        let row = sqlx::query(
+            "INSERT INTO leases (name, holder, expires_at) \
+                VALUES ($1, $2, EXTRACT(EPOCH FROM now())::BIGINT + $3) \
+                ON CONFLICT (name) DO UPDATE SET holder = EXCLUDED.holder, expires_at = EXCLUDED.expires_at \
+                WHERE leases.holder = EXCLUDED.holder \
+                    OR leases.expires_at < EXTRACT(EPOCH FROM now())::BIGINT \
+                RETURNING holder",
+        )
+        .bind(name)
+        .bind(holder)
+        .bind(ttl)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.is_some())
+    }
+
+    /// Stores slot ranges of backfill units, returns the amount of new units
+    #[tracing::instrument(level = "debug", skip(self, ranges), fields(count = ranges.len()))]
+    async fn insert_backfill_units(&self, ranges: &[(Slot, Slot)]) -> DbResult<u64> {
+        let mut inserted = 0;
+        for (start_slot, end_slot) in ranges {
+            inserted += sqlx::query(
+                "INSERT INTO backfill_units (start_slot, end_slot) VALUES ($1, $2) \
+                    ON CONFLICT (start_slot, end_slot) DO NOTHING;",
+            )
+            .bind(*start_slot as i64)
+            .bind(*end_slot as i64)
+            .execute(&self.pool)
+            .await?
+            .rows_affected();
+        }
+
+        Ok(inserted)
+    }
+
+    /// Claims the oldest pending unit, or a unit whose worker didn't report for `claim_timeout` seconds.
+    /// Units locked by concurrent claims are skipped.
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn claim_backfill_unit(
+        &self,
+        worker: &str,
+        claim_timeout: i64,
+    ) -> DbResult<Option<BackfillUnit>> {
+        let now = Utc::now().timestamp();
+        let row = sqlx::query(
+            "UPDATE backfill_units SET status = 'claimed', worker = $1, claimed_at = $2 \
+                WHERE id = ( \
+                    SELECT id FROM backfill_units \
+                    WHERE status = 'pending' OR (status = 'claimed' AND claimed_at < $3) \
+                    ORDER BY start_slot LIMIT 1 FOR UPDATE SKIP LOCKED \
+                ) \
+                RETURNING id, start_slot, end_slot, status, worker",
+        )
+        .bind(worker)
+        .bind(now)
+        .bind(now - claim_timeout)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|row| BackfillUnit {
+            id: row.get("id"),
+            start_slot: row.get::<i64, _>("start_slot") as Slot,
+            end_slot: row.get::<i64, _>("end_slot") as Slot,
+            status: row.get("status"),
+            worker: row.get("worker"),
+        }))
+    }
+
+    /// Sets the state of a unit claimed by the worker, the claim is renewed.
+    /// Returns false if the unit was claimed by another worker meanwhile.
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn update_backfill_unit(
+        &self,
+        id: i64,
+        worker: &str,
+        status: BackfillStatus,
+    ) -> DbResult<bool> {
+        let updated = sqlx::query(
+            "UPDATE backfill_units SET status = $1, claimed_at = $2 WHERE id = $3 AND worker = $4;",
+        )
+        .bind(status)
+        .bind(Utc::now().timestamp())
+        .bind(id)
+        .bind(worker)
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+
+        Ok(updated > 0)
+    }
+
+    /// Returns the amount of backfill units by their state
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn get_backfill_progress(&self) -> DbResult<BackfillProgress> {
+        let rows =
+            sqlx::query("SELECT status, COUNT(*) AS count FROM backfill_units GROUP BY status")
+                .fetch_all(&self.pool)
+                .await?;
+
+        let mut progress = BackfillProgress::default();
+        for row in rows {
+            let count = row.get("count");
+            match row.get("status") {
+                BackfillStatus::Pending => progress.pending = count,
+                BackfillStatus::Claimed => progress.claimed = count,
+                BackfillStatus::Completed => progress.completed = count,
+            }
+        }
+
+        Ok(progress)
+    }
+
+    /// Records the instruction and stores the messages of its outbox in a single transaction.
+    /// Messages are dropped if the instruction was already recorded.
+    /// Returns false without storing anything if the `lease` isn't held anymore.
+    #[tracing::instrument(level = "debug", skip(self, outbox), fields(messages = outbox.messages().len()))]
+    async fn insert_instruction_with_outbox(
+        &self,
+        instruction: &Instruction,
+        outbox: &Outbox,
+        lease: Option<&LeaseGuard>,
+    ) -> DbResult<bool> {
+        let mut transaction = self.pool.begin().await?;
+
+        if let Some(lease) = lease {
+            // Locks the lease, so it can't be taken over until the instruction is committed
+            let held = sqlx::query(
+                "SELECT 1 FROM leases WHERE name = $1 AND holder = $2 \
+                    AND expires_at >= EXTRACT(EPOCH FROM now())::BIGINT FOR SHARE",
+            )
+            .bind(lease.name())
+            .bind(lease.holder())
+            .fetch_optional(&mut *transaction)
+            .await?
+            .is_some();
+
+            if !held {
+                return Ok(false);
+            }
+        }
+
+        let inserted = sqlx::query(
+            "INSERT INTO instructions (id, tx_hash, program_id, blocktime, data) \
+                VALUES ($1, $2, $3, $4, $5) ON CONFLICT (id) DO NOTHING;",
+        )
+        .bind(instruction.idempotency_key())
+        .bind(&instruction.tx_hash)
+        .bind(&instruction.program_id)
+        .bind(instruction.blocktime)
+        .bind(&instruction.data)
+        .execute(&mut *transaction)
+        .await?
+        .rows_affected();
+
+        if inserted > 0 {
+            let created_at = Utc::now().timestamp();
+            for (key, message) in outbox.message_keys() {
+                sqlx::query(
+                    "INSERT INTO outbox (idempotency_key, topic, payload, created_at) \
+                        VALUES ($1, $2, $3, $4) ON CONFLICT (idempotency_key) DO NOTHING;",
+                )
+                .bind(key)
+                .bind(&message.topic)
+                .bind(&message.payload)
+                .bind(created_at)
+                .execute(&mut *transaction)
+                .await?;
+            }
+        }
+
+        transaction.commit().await?;
+        Ok(true)
+    }
+
+    /// Returns the oldest undelivered messages of the topic, or of every topic if it's `None`,
+    /// in the order they were queued. Dead-lettered messages are left out.
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn get_undelivered_messages(
+        &self,
+        topic: Option<&str>,
+        limit: i64,
+    ) -> DbResult<Vec<OutboxRecord>> {
+        let rows = sqlx::query(
+            "SELECT id, idempotency_key, topic, payload, attempts, last_error FROM outbox \
+                WHERE delivered_at IS NULL AND dead_lettered_at IS NULL \
+                    AND ($1::VARCHAR IS NULL OR topic = $1) \
+                ORDER BY id LIMIT $2",
+        )
+        .bind(topic)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| OutboxRecord {
+                id: row.get("id"),
+                idempotency_key: row.get("idempotency_key"),
+                topic: row.get("topic"),
+                payload: row.get("payload"),
+                attempts: row.get("attempts"),
+                last_error: row.get("last_error"),
+            })
+            .collect())
+    }
+
+    /// Marks the message as delivered
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn mark_delivered(&self, id: i64) -> DbResult<()> {
+        sqlx::query("UPDATE outbox SET delivered_at = $1 WHERE id = $2")
+            .bind(Utc::now().timestamp())
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Counts a failed delivery of the message
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn record_delivery_failure(&self, id: i64, error: &str) -> DbResult<()> {
+        sqlx::query("UPDATE outbox SET attempts = attempts + 1, last_error = $1 WHERE id = $2")
+            .bind(error)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Gives up the message the receiver rejected, it isn't delivered anymore
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn dead_letter_message(&self, id: i64, error: &str) -> DbResult<()> {
+        sqlx::query(
+            "UPDATE outbox SET attempts = attempts + 1, last_error = $1, dead_lettered_at = $2 \
+                WHERE id = $3",
+        )
+        .bind(error)
+        .bind(Utc::now().timestamp())
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Logs a request to a webhook
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn insert_webhook_delivery(&self, record: &WebhookDeliveryRecord) -> DbResult<()> {
+        sqlx::query(
+            "INSERT INTO webhook_deliveries (url, keys, attempt, status, error, occurred_at) \
+                VALUES ($1, $2, $3, $4, $5, $6);",
+        )
+        .bind(&record.url)
+        .bind(&record.keys)
+        .bind(record.attempt)
+        .bind(record.status)
+        .bind(&record.error)
+        .bind(record.occurred_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Returns the latest requests to webhooks, the newest first
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn get_webhook_deliveries(&self, limit: i64) -> DbResult<Vec<WebhookDeliveryRecord>> {
+        let rows = sqlx::query(
+            "SELECT url, keys, attempt, status, error, occurred_at \
+                FROM webhook_deliveries ORDER BY id DESC LIMIT $1",
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| WebhookDeliveryRecord {
+                url: row.get("url"),
+                keys: row.get("keys"),
+                attempt: row.get("attempt"),
+                status: row.get("status"),
+                error: row.get("error"),
+                occurred_at: row.get("occurred_at"),
+            })
+            .collect())
+    }
+
+    /// Gives up the lease if it is held by the holder
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn release_lease(&self, name: &str, holder: &str) -> DbResult<()> {
+        sqlx::query("DELETE FROM leases WHERE name = $1 AND holder = $2")
+            .bind(name)
+            .bind(holder)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Whether the lease is held by the holder and hasn't expired
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn holds_lease(&self, name: &str, holder: &str) -> DbResult<bool> {
+        let row = sqlx::query(
+            "SELECT 1 FROM leases WHERE name = $1 AND holder = $2 \
+                AND expires_at >= EXTRACT(EPOCH FROM now())::BIGINT",
+        )
+        .bind(name)
+        .bind(holder)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.is_some())
+    }
 }
@@ -2,9 +2,12 @@ use {
     super::*,
     crate::{
         db::test_connection_manager::ConnectionManager,
-        fetcher::{IndexingStatus, Tx},
-        processor::Instruction,
+        executor::TxSignature,
+        fetcher::{IndexingStatus, Tx, TxCommitment},
+        processor::{Instruction, NativeProcessingError, Outbox, ProcessingError},
+        ErrorCategory, ErrorContext, IndexerError, LeaseGuard,
     },
+    solana_sdk::clock::Slot,
     sqlx::Row,
 };
 
@@ -16,10 +19,12 @@ async fn transaction_test() {
 
     let mut tx = Tx {
         hash: "test_hash".to_string(),
+        slot: 123,
         blocktime: 123,
         instructions: vec![],
         account_keys: vec![],
         indexing_status: IndexingStatus::Pending,
+        commitment: TxCommitment::Finalized,
         indexing_timestamp: 123,
     };
 
@@ -113,19 +118,23 @@ async fn most_recent_tx_test() {
 
     let earliest_tx = Tx {
         hash: "earliest_tx".to_string(),
+        slot: 123,
         blocktime: 111,
         instructions: vec![],
         account_keys: vec![],
         indexing_status: IndexingStatus::Pending,
+        commitment: TxCommitment::Finalized,
         indexing_timestamp: 123,
     };
 
     let recent_tx = Tx {
         hash: "recent_tx".to_string(),
+        slot: 123,
         blocktime: 123,
         instructions: vec![],
         account_keys: vec![],
         indexing_status: IndexingStatus::Pending,
+        commitment: TxCommitment::Finalized,
         indexing_timestamp: 123,
     };
 
@@ -156,10 +165,12 @@ async fn recorded_tx_test() {
 
     let tx = Tx {
         hash: "test_hash".to_string(),
+        slot: 123,
         blocktime: 111,
         instructions: vec![],
         account_keys: vec![],
         indexing_status: IndexingStatus::Indexed,
+        commitment: TxCommitment::Finalized,
         indexing_timestamp: 123,
     };
 
@@ -200,3 +211,482 @@ async fn recorded_instruction_test() {
         .await
         .expect("Failed to get instruction"));
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn cursor_test() {
+    let connection_manager = ConnectionManager::build().await;
+    let db_manager = DbManager::connect(connection_manager.get_connection_options())
+        .expect("Failed to create manager");
+
+    assert_eq!(
+        db_manager
+            .get_cursor("test_cursor")
+            .await
+            .expect("Failed to get cursor"),
+        None
+    );
+
+    let cursor = Cursor {
+        slot: Some(123),
+        signature: None,
+    };
+    db_manager
+        .set_cursor("test_cursor", &cursor)
+        .await
+        .expect("Failed to set cursor");
+
+    let cursor = Cursor {
+        slot: Some(456),
+        signature: Some("test_hash".to_string()),
+    };
+    db_manager
+        .set_cursor("test_cursor", &cursor)
+        .await
+        .expect("Failed to set cursor");
+
+    assert_eq!(
+        db_manager
+            .get_cursor("test_cursor")
+            .await
+            .expect("Failed to get cursor"),
+        Some(cursor)
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn commitment_test() {
+    let connection_manager = ConnectionManager::build().await;
+    let db_manager = DbManager::connect(connection_manager.get_connection_options())
+        .expect("Failed to create manager");
+
+    let mut tx = Tx {
+        hash: "confirmed_tx".to_string(),
+        slot: 123,
+        blocktime: 111,
+        instructions: vec![],
+        account_keys: vec![],
+        indexing_status: IndexingStatus::Indexed,
+        commitment: TxCommitment::Confirmed,
+        indexing_timestamp: 123,
+    };
+    db_manager
+        .insert_transaction(&tx)
+        .await
+        .expect("Failed to insert transaction");
+
+    tx.hash = "finalized_tx".to_string();
+    tx.commitment = TxCommitment::Finalized;
+    db_manager
+        .insert_transaction(&tx)
+        .await
+        .expect("Failed to insert transaction");
+
+    let confirmed = db_manager
+        .get_confirmed_txs(10)
+        .await
+        .expect("Failed to get confirmed transactions");
+    assert_eq!(confirmed, vec![("confirmed_tx".to_string(), 123)]);
+
+    db_manager
+        .update_commitment("confirmed_tx", TxCommitment::Finalized)
+        .await
+        .expect("Failed to update commitment");
+
+    assert!(db_manager
+        .get_confirmed_txs(10)
+        .await
+        .expect("Failed to get confirmed transactions")
+        .is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn pending_signatures_test() {
+    let connection_manager = ConnectionManager::build().await;
+    let db_manager = DbManager::connect(connection_manager.get_connection_options())
+        .expect("Failed to create manager");
+
+    let signature = |signature: &str, slot: Slot| TxSignature {
+        signature: signature.to_string(),
+        slot,
+        err: None,
+        memo: None,
+        block_time: Some(123),
+        confirmation_status: None,
+    };
+
+    // Pages are collected from the newest signature to the oldest one
+    db_manager
+        .insert_pending_signatures("", &vec![signature("third", 2), signature("second", 1)])
+        .await
+        .expect("Failed to insert signatures");
+    db_manager
+        .insert_pending_signatures("", &vec![signature("second", 1), signature("first", 1)])
+        .await
+        .expect("Failed to insert signatures");
+
+    // Signatures of other scopes are kept apart
+    db_manager
+        .insert_pending_signatures("range", &vec![signature("first", 1)])
+        .await
+        .expect("Failed to insert signatures");
+
+    let pending = db_manager
+        .get_pending_signatures("", 10)
+        .await
+        .expect("Failed to get signatures");
+    let order = pending
+        .iter()
+        .map(|sign| sign.signature.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(order, vec!["first", "second", "third"]);
+
+    db_manager
+        .delete_pending_signatures("", &pending[..2].to_vec())
+        .await
+        .expect("Failed to delete signatures");
+    assert_eq!(
+        db_manager
+            .get_pending_signatures("", 10)
+            .await
+            .expect("Failed to get signatures"),
+        vec![signature("third", 2)]
+    );
+
+    db_manager
+        .clear_pending_signatures("")
+        .await
+        .expect("Failed to clear signatures");
+    assert!(db_manager
+        .get_pending_signatures("", 10)
+        .await
+        .expect("Failed to get signatures")
+        .is_empty());
+    assert_eq!(
+        db_manager
+            .get_pending_signatures("range", 10)
+            .await
+            .expect("Failed to get signatures"),
+        vec![signature("first", 1)]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn raw_transactions_test() {
+    let connection_manager = ConnectionManager::build().await;
+    let db_manager = DbManager::connect(connection_manager.get_connection_options())
+        .expect("Failed to create manager");
+
+    let record = |hash: &str, slot: Slot| RawTxRecord {
+        hash: hash.to_string(),
+        slot,
+        data: vec![1, 2, 3],
+    };
+
+    for record in [
+        record("b", 2),
+        record("a", 2),
+        record("c", 1),
+        record("d", 5),
+    ] {
+        db_manager
+            .insert_raw_transaction(&record)
+            .await
+            .expect("Failed to insert raw transaction");
+    }
+
+    let page = db_manager
+        .get_raw_transactions(1, 4, None, 2)
+        .await
+        .expect("Failed to get raw transactions");
+    assert_eq!(page, vec![record("c", 1), record("a", 2)]);
+
+    let page = db_manager
+        .get_raw_transactions(1, 4, page.last(), 2)
+        .await
+        .expect("Failed to get raw transactions");
+    assert_eq!(page, vec![record("b", 2)]);
+
+    let instruction = Instruction::new(
+        1,
+        "a".to_string(),
+        "test_id".to_string(),
+        123,
+        vec![],
+        "empty_data".to_string(),
+    );
+    db_manager
+        .insert_instruction(&instruction)
+        .await
+        .expect("Failed to insert instruction");
+    db_manager
+        .delete_instructions(&["a".to_string()])
+        .await
+        .expect("Failed to delete instructions");
+
+    assert!(!db_manager
+        .recorded_instruction(&instruction)
+        .await
+        .expect("Failed to get instruction"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn status_test() {
+    let connection_manager = ConnectionManager::build().await;
+    let db_manager = DbManager::connect(connection_manager.get_connection_options())
+        .expect("Failed to create manager");
+
+    let mut tx = Tx {
+        hash: "pending_tx".to_string(),
+        slot: 123,
+        blocktime: 111,
+        instructions: vec![],
+        account_keys: vec![],
+        indexing_status: IndexingStatus::Pending,
+        commitment: TxCommitment::Finalized,
+        indexing_timestamp: 123,
+    };
+    db_manager
+        .insert_transaction(&tx)
+        .await
+        .expect("Failed to insert transaction");
+
+    tx.hash = "indexed_tx".to_string();
+    tx.indexing_status = IndexingStatus::Indexed;
+    db_manager
+        .insert_transaction(&tx)
+        .await
+        .expect("Failed to insert transaction");
+
+    assert_eq!(
+        db_manager
+            .count_pending_txs()
+            .await
+            .expect("Failed to count transactions"),
+        1
+    );
+    let pending = db_manager
+        .get_pending_txs()
+        .await
+        .expect("Failed to get transactions");
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].signature, "pending_tx");
+    assert_eq!(pending[0].slot, 123);
+
+    assert_eq!(
+        db_manager
+            .get_last_error()
+            .await
+            .expect("Failed to get error"),
+        None
+    );
+    for message in ["first error", "second error"] {
+        db_manager
+            .insert_error(message)
+            .await
+            .expect("Failed to insert error");
+    }
+    assert_eq!(
+        db_manager
+            .get_last_error()
+            .await
+            .expect("Failed to get error")
+            .map(|error| error.message),
+        Some("second error".to_string())
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dead_letters_test() {
+    let connection_manager = ConnectionManager::build().await;
+    let db_manager = DbManager::connect(connection_manager.get_connection_options())
+        .expect("Failed to create manager");
+
+    let error = IndexerError::from(ProcessingError::from(
+        NativeProcessingError::InstructionWithoutAccounts,
+    ))
+    .with_context(ErrorContext::transaction("bad_tx", Some(123)).with_instruction(2));
+    db_manager
+        .dead_letter(&error)
+        .await
+        .expect("Failed to insert dead letter");
+
+    let dead_letters = db_manager
+        .get_dead_letters(10)
+        .await
+        .expect("Failed to get dead letters");
+    assert_eq!(dead_letters.len(), 1);
+    assert_eq!(dead_letters[0].signature.as_deref(), Some("bad_tx"));
+    assert_eq!(dead_letters[0].slot, Some(123));
+    assert_eq!(dead_letters[0].instruction, Some(2));
+    assert_eq!(dead_letters[0].category, ErrorCategory::Data.to_string());
+    assert_eq!(dead_letters[0].message, "Instruction without account keys");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn lease_test() {
+    let connection_manager = ConnectionManager::build().await;
+    let db_manager = DbManager::connect(connection_manager.get_connection_options())
+        .expect("Failed to create manager");
+
+    assert!(db_manager
+        .try_acquire_lease("lease", "first", 30)
+        .await
+        .unwrap());
+    assert!(!db_manager
+        .try_acquire_lease("lease", "second", 30)
+        .await
+        .unwrap());
+    assert!(db_manager
+        .try_acquire_lease("lease", "first", 30)
+        .await
+        .unwrap());
+
+    // An expired lease is taken over
+    assert!(db_manager
+        .try_acquire_lease("lease", "first", -1)
+        .await
+        .unwrap());
+    assert!(db_manager
+        .try_acquire_lease("lease", "second", 30)
+        .await
+        .unwrap());
+
+    db_manager.release_lease("lease", "first").await.unwrap();
+    assert!(!db_manager
+        .try_acquire_lease("lease", "first", 30)
+        .await
+        .unwrap());
+    db_manager.release_lease("lease", "second").await.unwrap();
+    assert!(db_manager
+        .try_acquire_lease("lease", "first", 30)
+        .await
+        .unwrap());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn fenced_instruction_test() {
+    let connection_manager = ConnectionManager::build().await;
+    let db_manager = DbManager::connect(connection_manager.get_connection_options())
+        .expect("Failed to create manager");
+
+    let lease = LeaseGuard::new("lease".to_string(), "first".to_string());
+    let instruction = |id| {
+        Instruction::new(
+            id,
+            "fenced_tx".to_string(),
+            "test_id".to_string(),
+            123,
+            vec![],
+            "empty_data".to_string(),
+        )
+    };
+    let insert = |instruction: Instruction, lease: Option<LeaseGuard>| {
+        let db_manager = db_manager.clone();
+        async move {
+            let outbox = Outbox::new(&instruction);
+            let held = db_manager
+                .insert_instruction_with_outbox(&instruction, &outbox, lease.as_ref())
+                .await
+                .expect("Failed to insert instruction");
+            let recorded = db_manager
+                .recorded_instruction(&instruction)
+                .await
+                .expect("Failed to get instruction");
+            (held, recorded)
+        }
+    };
+
+    // Nothing is recorded before the lease is acquired
+    assert!(!db_manager.holds_lease("lease", "first").await.unwrap());
+    assert_eq!(
+        insert(instruction(0), Some(lease.clone())).await,
+        (false, false)
+    );
+
+    assert!(db_manager
+        .try_acquire_lease("lease", "first", 30)
+        .await
+        .unwrap());
+    assert!(db_manager.holds_lease("lease", "first").await.unwrap());
+    assert_eq!(
+        insert(instruction(1), Some(lease.clone())).await,
+        (true, true)
+    );
+
+    // The former holder can't record instructions once the lease is taken over
+    assert!(db_manager
+        .try_acquire_lease("lease", "first", -1)
+        .await
+        .unwrap());
+    assert!(db_manager
+        .try_acquire_lease("lease", "second", 30)
+        .await
+        .unwrap());
+    assert!(!db_manager.holds_lease("lease", "first").await.unwrap());
+    assert_eq!(insert(instruction(2), Some(lease)).await, (false, false));
+
+    assert_eq!(insert(instruction(3), None).await, (true, true));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn backfill_units_test() {
+    let connection_manager = ConnectionManager::build().await;
+    let db_manager = DbManager::connect(connection_manager.get_connection_options())
+        .expect("Failed to create manager");
+
+    let ranges = [(0, 99), (100, 199)];
+    assert_eq!(db_manager.insert_backfill_units(&ranges).await.unwrap(), 2);
+    assert_eq!(db_manager.insert_backfill_units(&ranges).await.unwrap(), 0);
+
+    let first = db_manager
+        .claim_backfill_unit("first", 600)
+        .await
+        .unwrap()
+        .expect("Unit is not claimed");
+    assert_eq!((first.start_slot, first.end_slot), (0, 99));
+    assert_eq!(first.status, BackfillStatus::Claimed);
+    assert_eq!(first.worker.as_deref(), Some("first"));
+
+    let second = db_manager
+        .claim_backfill_unit("second", 600)
+        .await
+        .unwrap()
+        .expect("Unit is not claimed");
+    assert_eq!((second.start_slot, second.end_slot), (100, 199));
+    assert_eq!(
+        db_manager.claim_backfill_unit("third", 600).await.unwrap(),
+        None
+    );
+
+    assert!(db_manager
+        .update_backfill_unit(first.id, "first", BackfillStatus::Completed)
+        .await
+        .unwrap());
+    assert_eq!(
+        db_manager.get_backfill_progress().await.unwrap(),
+        BackfillProgress {
+            pending: 0,
+            claimed: 1,
+            completed: 1,
+        }
+    );
+
+    // The unit of a silent worker is claimed again
+    let reclaimed = db_manager
+        .claim_backfill_unit("third", -1)
+        .await
+        .unwrap()
+        .expect("Unit is not claimed");
+    assert_eq!(reclaimed.id, second.id);
+    assert_eq!(reclaimed.worker.as_deref(), Some("third"));
+
+    // The former worker can't renew or complete the unit anymore
+    assert!(!db_manager
+        .update_backfill_unit(second.id, "second", BackfillStatus::Claimed)
+        .await
+        .unwrap());
+    assert!(!db_manager
+        .update_backfill_unit(second.id, "second", BackfillStatus::Completed)
+        .await
+        .unwrap());
+}
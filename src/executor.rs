@@ -1,7 +1,8 @@
 use {
     crate::{
-        fetcher::{FetchingResult, Tx},
-        CallbackResult, Instruction,
+        fetcher::{Tx, TxCommitment},
+        processor::Outbox,
+        CallbackResult, IndexingResult, Instruction,
     },
     futures::{lock::Mutex, Future},
     std::sync::Arc,
@@ -43,6 +44,17 @@ pub trait ExecutorCallback {
         async { Ok(ExecutorControlFlow::Pass) }
     }
 
+    /// Processes the instruction queueing its side effects into the outbox.
+    /// The messages are stored together with the instruction marker if `Pass` is returned,
+    /// and delivered later by an [OutboxDispatcher](crate::OutboxDispatcher).
+    fn process_instruction_with_outbox(
+        &mut self,
+        instruction: &Instruction,
+        outbox: &mut Outbox,
+    ) -> impl Future<Output = CbResult> + Send {
+        self.process_instruction(instruction)
+    }
+
     fn process_parsed_transaction(&mut self, tx: &Tx) -> impl Future<Output = TxResult> + Send {
         async {
             Ok(ControlFlowWithData {
@@ -79,6 +91,23 @@ pub trait ExecutorCallback {
     fn process_signature(&mut self, tx: &TxSignature) -> impl Future<Output = CbResult> + Send {
         async { Ok(ExecutorControlFlow::Pass) }
     }
+
+    /// Called once the instructions of a batch are processed, before the batch is recorded as
+    /// indexed. Executors that don't wait for their side effects in other hooks complete them
+    /// here, an error makes the instructions of the batch be processed again.
+    fn complete_batch(&mut self) -> impl Future<Output = CallbackResult<()>> + Send {
+        async { Ok(()) }
+    }
+
+    /// Called when a transaction indexed at confirmed commitment is finalized or dropped.
+    /// Returning `Stop` postpones the remaining updates until the next indexing iteration.
+    fn process_commitment_change(
+        &mut self,
+        tx_hash: &str,
+        commitment: TxCommitment,
+    ) -> impl Future<Output = CbResult> + Send {
+        async { Ok(ExecutorControlFlow::Pass) }
+    }
 }
 
 impl ExecutorCallback for () {}
@@ -102,5 +131,5 @@ pub struct ControlFlowWithData<D> {
     pub data: D,
 }
 
-pub type TxResult = CallbackResult<ControlFlowWithData<Option<FetchingResult<Tx>>>>;
+pub type TxResult = CallbackResult<ControlFlowWithData<Option<IndexingResult<Tx>>>>;
 pub type CbResult = CallbackResult<ExecutorControlFlow>;
@@ -1,7 +1,6 @@
 use {
-    crate::CallbackError, solana_client::client_error::ClientError,
-    solana_program::pubkey::ParsePubkeyError, solana_sdk::signature::ParseSignatureError,
-    thiserror::Error,
+    solana_client::client_error::ClientError, solana_program::pubkey::ParsePubkeyError,
+    solana_sdk::signature::ParseSignatureError, thiserror::Error,
 };
 
 pub type FetchingResult<T> = std::result::Result<T, FetchingError>;
@@ -33,7 +32,5 @@ pub enum FetchingError {
     #[error(transparent)]
     ParsePubkey(#[from] ParsePubkeyError),
     #[error(transparent)]
-    DbError(#[from] sqlx::Error),
-    #[error(transparent)]
-    CbError(#[from] CallbackError),
+    RawTxCodec(#[from] std::io::Error),
 }
@@ -2,42 +2,94 @@ use solana_transaction_status::option_serializer::OptionSerializer;
 
 use {
     enum_extract::let_extract,
+    futures::{future::BoxFuture, FutureExt},
     solana_client::{
-        nonblocking::rpc_client::RpcClient, rpc_client::GetConfirmedSignaturesForAddress2Config,
+        client_error::Result as ClientResult, nonblocking::rpc_client::RpcClient,
+        rpc_client::GetConfirmedSignaturesForAddress2Config, rpc_config::RpcBlockConfig,
         rpc_response::RpcConfirmedTransactionStatusWithSignature,
     },
     solana_program::pubkey::Pubkey,
-    solana_sdk::{commitment_config::CommitmentConfig, signature::Signature},
+    solana_sdk::{clock::Slot, commitment_config::CommitmentConfig, signature::Signature},
     solana_transaction_status::{
-        parse_accounts::ParsedAccount, EncodedConfirmedTransactionWithStatusMeta,
-        EncodedTransaction, UiMessage, UiTransactionEncoding,
+        parse_accounts::{ParsedAccount, ParsedAccountSource},
+        EncodedConfirmedTransactionWithStatusMeta, EncodedTransaction,
+        TransactionConfirmationStatus, TransactionDetails, UiMessage, UiTransactionEncoding,
+    },
+    std::{
+        str::FromStr,
+        time::{Duration, Instant},
     },
-    std::{str::FromStr, time::Duration},
     tokio::time::sleep,
     tracing::info,
 };
 
 use crate::{
     configuration::Configuration,
-    db::{DbManager, IndexerDbRecording},
-    fetcher::{FetchingResult, FetchingSettings, NativeFetchingError, Tx},
-    indexer::IndexerReport,
+    db::{DbManager, IndexerDbRecording, RawTxRecord},
+    executor::TxResult,
+    fetcher::{
+        compress_raw_tx, decompress_raw_tx, FetchingError, FetchingResult, FetchingSettings,
+        FetchingStrategy, NativeFetchingError, RateLimiter, RpcMethod, RpcPool, Tx, TxCommitment,
+    },
+    indexer::{
+        indexer_settings::{CommitmentMode, IndexerSettings},
+        IndexerReport,
+    },
     utils::{fibonacci, is_acc_signer, is_acc_writable},
-    Executor, ExecutorCallback,
+    ControlFlowWithData, ErrorCategory, ErrorContext, Executor, ExecutorCallback,
+    ExecutorControlFlow, IndexingResult,
 };
 
 pub type TxBatch = Vec<RpcConfirmedTransactionStatusWithSignature>;
 
+/// Maximum amount of signatures accepted by `getSignatureStatuses`
+const MAX_SIGNATURE_STATUSES: usize = 256;
+
+/// Result of passing a fetched transaction through the executor hooks
+enum RawTxOutcome {
+    /// Transaction is parsed and ready to be stored
+    Tx(Tx),
+
+    /// Executor asked to skip the transaction
+    Skip,
+
+    /// Executor stopped the hooks chain with its own result
+    Stop(IndexingResult<Tx>),
+}
+
+impl RawTxOutcome {
+    /// Converts result of a hook into an outcome if the hook interrupts the chain
+    fn interrupted_by(hook: &str, result: TxResult) -> IndexingResult<Option<Self>> {
+        let result = result?;
+
+        Ok(match result.control_flow {
+            ExecutorControlFlow::Skip => Some(RawTxOutcome::Skip),
+            ExecutorControlFlow::Pass => None,
+            ExecutorControlFlow::Stop => {
+                Some(RawTxOutcome::Stop(result.data.unwrap_or_else(|| {
+                    Err(anyhow::anyhow!("fetch_tx failed in {hook} executor.").into())
+                })))
+            }
+        })
+    }
+}
+
 pub struct FetchingManager<E> {
-    /// A client of a remote Solana node
-    rpc_client: RpcClient,
+    /// Clients of remote Solana nodes
+    rpc_pool: RpcPool,
 
     /// The public key of the account containing a program
     program_id: Pubkey,
 
+    /// Commitment level of fetched data
+    commitment: CommitmentMode,
+
     /// Settings for fetching
     fetching_settings: FetchingSettings,
 
+    /// Client-side limiter of RPC calls
+    rate_limiter: RateLimiter,
+
     /// Indexer status report
     report: IndexerReport,
 
@@ -58,25 +110,63 @@ where
         report: IndexerReport,
         db_manager: DbManager,
     ) -> FetchingResult<Self> {
-        let fetching_settings = if let Some(settings) = &config.fetcher_settings {
-            settings.clone()
-        } else {
-            FetchingSettings::default()
-        };
+        Self::with_client(
+            &config.indexer_settings,
+            config.fetcher_settings.clone(),
+            None,
+            report,
+            db_manager,
+        )
+    }
+
+    /// Initializes new instance of fetching manager, `rpc_client` replaces the client of `connection_str`
+    pub fn with_client(
+        indexer_settings: &IndexerSettings,
+        fetching_settings: Option<FetchingSettings>,
+        rpc_client: Option<RpcClient>,
+        report: IndexerReport,
+        db_manager: DbManager,
+    ) -> FetchingResult<Self> {
+        let fetching_settings = fetching_settings.unwrap_or_default();
+
+        let rate_limiter = fetching_settings
+            .rate_limits
+            .as_ref()
+            .map(RateLimiter::new)
+            .unwrap_or_default();
 
-        let rpc_timeout = match config.indexer_settings.rpc_timeout {
+        let commitment = indexer_settings.commitment.unwrap_or_default();
+
+        let rpc_timeout = match indexer_settings.rpc_timeout {
             Some(seconds) => Duration::from_secs(seconds),
             None => Duration::from_secs(10),
         };
 
-        Ok(Self {
-            rpc_client: RpcClient::new_with_timeout_and_commitment(
-                config.indexer_settings.connection_str.clone(),
+        let endpoints = indexer_settings
+            .rpc_endpoints
+            .as_deref()
+            .unwrap_or_default();
+        let rpc_pool = match rpc_client {
+            Some(client) => RpcPool::with_client(
+                client,
+                endpoints,
                 rpc_timeout,
-                CommitmentConfig::confirmed(),
+                commitment.commitment_config(),
             ),
-            program_id: Pubkey::from_str(&config.indexer_settings.program_id)?,
+            None => RpcPool::new(
+                &indexer_settings.connection_str,
+                endpoints,
+                rpc_timeout,
+                commitment.commitment_config(),
+            ),
+        };
+
+        Ok(Self {
+            rpc_pool,
+            program_id: Pubkey::from_str(&indexer_settings.program_id)?,
+            commitment,
             fetching_settings,
+            rate_limiter,
             report,
             db_manager,
             executor: Executor::None,
@@ -87,15 +177,43 @@ where
     #[cfg(test)]
     pub fn new_mock(connection_str: String, report: IndexerReport, db_manager: DbManager) -> Self {
         Self {
-            rpc_client: RpcClient::new_mock(connection_str),
+            rpc_pool: RpcPool::from_client(
+                connection_str.clone(),
+                RpcClient::new_mock(connection_str),
+            ),
             program_id: Pubkey::default(),
+            commitment: CommitmentMode::default(),
             fetching_settings: FetchingSettings::default(),
+            rate_limiter: RateLimiter::default(),
             report,
             db_manager,
             executor: Executor::None,
         }
     }
 
+    /// Returns the strategy of fetching transactions
+    pub fn strategy(&self) -> FetchingStrategy {
+        self.fetching_settings.strategy
+    }
+
+    /// Returns settings of the fetching process
+    pub fn fetching_settings(&self) -> &FetchingSettings {
+        &self.fetching_settings
+    }
+
+    /// Replaces settings of the fetching process, the strategy is kept
+    pub fn set_fetching_settings(&mut self, fetching_settings: FetchingSettings) {
+        self.rate_limiter = fetching_settings
+            .rate_limits
+            .as_ref()
+            .map(RateLimiter::new)
+            .unwrap_or_default();
+        self.fetching_settings = FetchingSettings {
+            strategy: self.fetching_settings.strategy,
+            ..fetching_settings
+        };
+    }
+
     pub fn set_executor(&mut self, executor: Executor<E>) {
         self.executor = executor;
     }
@@ -105,23 +223,36 @@ where
         R: ExecutorCallback + Send + Sync + 'static,
     {
         let FetchingManager {
-            rpc_client,
+            rpc_pool,
             program_id,
+            commitment,
             fetching_settings,
+            rate_limiter,
             report,
             db_manager,
             ..
         } = self;
         FetchingManager {
             executor,
-            rpc_client,
+            rpc_pool,
             program_id,
+            commitment,
             fetching_settings,
+            rate_limiter,
             report,
             db_manager,
         }
     }
 
+    /// Waits for the rate limiter to allow the call of the method
+    async fn throttle(&self, method: RpcMethod) {
+        let wait = self.rate_limiter.acquire(method).await;
+
+        if !wait.is_zero() {
+            self.report.inc_throttle_wait(method, wait);
+        }
+    }
+
     #[tracing::instrument(level = "trace", skip(self))]
     pub async fn retry_delay(&self, error_occured: u64) -> FetchingResult<()> {
         if error_occured >= self.fetching_settings.retry_limit {
@@ -174,15 +305,19 @@ where
             before: sign_before,
             until: sign_until,
             limit: Some(self.fetching_settings.transaction_batch_size),
-            commitment: Some(CommitmentConfig::confirmed()),
+            commitment: Some(self.commitment.commitment_config()),
         };
 
-        let result = self
-            .rpc_client
+        self.throttle(RpcMethod::GetSignaturesForAddress).await;
+        let endpoint = self.rpc_pool.select();
+        let started_at = Instant::now();
+        let result = endpoint
+            .client()
             .get_signatures_for_address_with_config(&self.program_id, config)
             .await;
 
-        self.report.inc_metrics(&result);
+        endpoint.record(result.is_ok(), started_at.elapsed());
+        self.report.inc_metrics(endpoint.url(), &result);
 
         match result {
             Ok(signatures) => {
@@ -192,129 +327,420 @@ where
             }
             Err(error) => {
                 self.report.set_unavailable().await;
-                tracing::debug!(error = %error, "Couldn't fetch signatures");
+                tracing::debug!(
+                    error = %error,
+                    endpoint = %endpoint.url(),
+                    "Couldn't fetch signatures"
+                );
                 None
             }
         }
     }
 
-    /// Returns list of fetched transactions
+    /// Returns list of fetched transactions.
+    /// Transactions that fail with a data error are dead-lettered and skipped.
     #[tracing::instrument(level = "trace", skip(self))]
-    pub async fn fetch_batch(&self, confirmed_signatures: &TxBatch) -> FetchingResult<Vec<Tx>> {
+    pub async fn fetch_batch(&self, confirmed_signatures: &TxBatch) -> IndexingResult<Vec<Tx>> {
         let mut txs = vec![];
 
         for sign in confirmed_signatures {
-            if let Executor::Executor(ref e) = self.executor {
-                let mut executor = e.lock().await;
-                let control_flow = executor.process_signature(sign).await?;
-                match control_flow {
-                    crate::ExecutorControlFlow::Skip => continue,
-                    crate::ExecutorControlFlow::Pass => (),
-                    crate::ExecutorControlFlow::Stop => break,
-                };
-            }
+            let result = self.fetch_signature(sign).await.map_err(|error| {
+                error.with_context(ErrorContext::transaction(&sign.signature, Some(sign.slot)))
+            });
 
-            if self.db_manager.recorded_tx(&sign.signature).await? {
-                continue;
+            match result {
+                Ok(ControlFlowWithData {
+                    control_flow: ExecutorControlFlow::Stop,
+                    ..
+                }) => break,
+                Ok(ControlFlowWithData { data, .. }) => txs.extend(data),
+                Err(error) if error.category() == ErrorCategory::Data => {
+                    self.db_manager.dead_letter(&error).await?
+                }
+                Err(error) => return Err(error),
             }
-            let signature = Signature::from_str(sign.signature.as_str())?;
-            txs.push(self.fetch_tx(signature).await?)
         }
         Ok(txs)
     }
 
+    /// Fetches a transaction unless the executor skips it or it was fetched before
+    async fn fetch_signature(
+        &self,
+        sign: &RpcConfirmedTransactionStatusWithSignature,
+    ) -> IndexingResult<ControlFlowWithData<Option<Tx>>> {
+        if let Executor::Executor(ref e) = self.executor {
+            let mut executor = e.lock().await;
+            let control_flow = executor.process_signature(sign).await?;
+            if control_flow != ExecutorControlFlow::Pass {
+                return Ok(ControlFlowWithData {
+                    control_flow,
+                    data: None,
+                });
+            }
+        }
+
+        let data = if self.db_manager.recorded_tx(&sign.signature).await? {
+            None
+        } else {
+            let signature = Signature::from_str(sign.signature.as_str())?;
+            Some(self.fetch_tx(signature).await?)
+        };
+        Ok(ControlFlowWithData {
+            control_flow: ExecutorControlFlow::Pass,
+            data,
+        })
+    }
+
     /// Returns fetched transaction
-    #[tracing::instrument(level = "trace", skip(self))]
-    async fn fetch_tx(&self, signature: Signature) -> FetchingResult<Tx> {
+    #[tracing::instrument(
+        name = "fetch_transaction",
+        level = "info",
+        skip(self),
+        fields(signature = %signature, program_id = %self.program_id)
+    )]
+    async fn fetch_tx(&self, signature: Signature) -> IndexingResult<Tx> {
         let mut error_occured = 0;
 
         loop {
-            let result = self
-                .rpc_client
+            self.throttle(RpcMethod::GetTransaction).await;
+            let endpoint = self.rpc_pool.select();
+            let started_at = Instant::now();
+            let result = endpoint
+                .client()
                 .get_transaction(&signature, UiTransactionEncoding::Json)
                 .await;
 
-            self.report.inc_metrics(&result);
+            endpoint.record(result.is_ok(), started_at.elapsed());
+            self.report.inc_metrics(endpoint.url(), &result);
 
             match result {
-                Ok(mut raw_tx) => {
+                Ok(raw_tx) => {
                     self.report.set_available().await;
                     tracing::debug!("Fetched transaction");
 
-                    if let Executor::Executor(ref e) = self.executor {
-                        let mut executor = e.lock().await;
-                        let res = executor.process_raw_transaction(&raw_tx).await?;
-                        match res.control_flow {
-                            crate::ExecutorControlFlow::Skip => continue,
-                            crate::ExecutorControlFlow::Pass => (),
-                            crate::ExecutorControlFlow::Stop => match res.data {
-                                Some(res) => break res,
-                                None => {
-                                    break Err(anyhow::anyhow!(
-                                        "fetch_tx failed in process_raw_transaction executor."
-                                    )
-                                    .into())
-                                }
-                            },
-                        };
-
-                        if let Some(messges) =
-                            raw_tx.transaction.meta.take().map(|mate| mate.log_messages)
-                        {
-                            if let OptionSerializer::Some(msgs) = messges {
-                                let res = executor.process_log_messages(msgs).await?;
-                                match res.control_flow {
-                                    crate::ExecutorControlFlow::Skip => continue,
-                                    crate::ExecutorControlFlow::Pass => (),
-                                    crate::ExecutorControlFlow::Stop => match res.data {
-                                        Some(res) => break res,
-                                        None => {
-                                            break Err(anyhow::anyhow!(
-                                                "fetch_tx failed in process_log_messages executor."
-                                            )
-                                            .into())
-                                        }
-                                    },
-                                };
-                            }
+                    let data = self.compress_if_stored(&raw_tx)?;
+                    match self.handle_raw_tx(raw_tx).await? {
+                        RawTxOutcome::Skip => continue,
+                        RawTxOutcome::Stop(result) => break result,
+                        RawTxOutcome::Tx(tx) => {
+                            self.insert_tx(&tx, data).await?;
+                            return Ok(tx);
                         }
                     }
+                }
+                Err(error) => {
+                    self.report.set_unavailable().await;
+                    tracing::debug!(
+                        error = %error,
+                        endpoint = %endpoint.url(),
+                        "Couldn't fetch transaction"
+                    );
 
-                    let tx = self.create_tx(raw_tx).await?;
-
-                    if let Executor::Executor(ref e) = self.executor {
-                        let mut executor = e.lock().await;
-                        let res = executor.process_parsed_transaction(&tx).await?;
-                        match res.control_flow {
-                            crate::ExecutorControlFlow::Skip => continue,
-                            crate::ExecutorControlFlow::Pass => (),
-                            crate::ExecutorControlFlow::Stop => match res.data {
-                                Some(res) => break res,
-                                None => {
-                                    break Err(anyhow::anyhow!(
-                                        "fetch_tx failed in process_parsed_transaction executor."
-                                    )
-                                    .into())
-                                }
-                            },
-                        };
-                    }
+                    error_occured += 1;
+                    // Note: rpc has its own timeout - 30 sec
+                    self.retry_delay(error_occured).await?;
+                }
+            }
+        }
+    }
+
+    /// Passes fetched transaction through the executor hooks and parses it
+    async fn handle_raw_tx(
+        &self,
+        mut raw_tx: EncodedConfirmedTransactionWithStatusMeta,
+    ) -> IndexingResult<RawTxOutcome> {
+        if let Executor::Executor(ref e) = self.executor {
+            let mut executor = e.lock().await;
+            let res = executor.process_raw_transaction(&raw_tx).await;
+            if let Some(outcome) = RawTxOutcome::interrupted_by("process_raw_transaction", res)? {
+                return Ok(outcome);
+            }
+
+            let log_messages = raw_tx
+                .transaction
+                .meta
+                .as_mut()
+                .map(|meta| std::mem::replace(&mut meta.log_messages, OptionSerializer::None));
+            if let Some(OptionSerializer::Some(msgs)) = log_messages {
+                let res = executor.process_log_messages(msgs).await;
+                if let Some(outcome) = RawTxOutcome::interrupted_by("process_log_messages", res)? {
+                    return Ok(outcome);
+                }
+            }
+        }
+
+        let tx = self.create_tx(raw_tx).await?;
+
+        if let Executor::Executor(ref e) = self.executor {
+            let mut executor = e.lock().await;
+            let res = executor.process_parsed_transaction(&tx).await;
+            if let Some(outcome) = RawTxOutcome::interrupted_by("process_parsed_transaction", res)?
+            {
+                return Ok(outcome);
+            }
+        }
 
-                    self.db_manager.insert_transaction(&tx).await?;
-                    return Ok(tx);
+        Ok(RawTxOutcome::Tx(tx))
+    }
+
+    /// Compresses fetched transaction if raw transactions are stored
+    fn compress_if_stored(
+        &self,
+        raw_tx: &EncodedConfirmedTransactionWithStatusMeta,
+    ) -> FetchingResult<Option<Vec<u8>>> {
+        if !self.fetching_settings.store_raw_transactions {
+            return Ok(None);
+        }
+
+        Ok(Some(compress_raw_tx(raw_tx)?))
+    }
+
+    /// Stores parsed transaction together with its compressed raw form
+    async fn insert_tx(&self, tx: &Tx, data: Option<Vec<u8>>) -> IndexingResult<()> {
+        self.db_manager.insert_transaction(tx).await?;
+
+        if let Some(data) = data {
+            let record = RawTxRecord {
+                hash: tx.hash.clone(),
+                slot: tx.slot,
+                data,
+            };
+            self.db_manager.insert_raw_transaction(&record).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Passes stored transaction through the executor hooks again without calling RPC node
+    #[tracing::instrument(level = "trace", skip(self, record), fields(hash = %record.hash))]
+    pub async fn replay_raw_tx(&self, record: &RawTxRecord) -> IndexingResult<Option<Tx>> {
+        let raw_tx = decompress_raw_tx(&record.data).map_err(FetchingError::from)?;
+
+        match self.handle_raw_tx(raw_tx).await? {
+            RawTxOutcome::Skip => Ok(None),
+            RawTxOutcome::Stop(result) => result.map(Some),
+            RawTxOutcome::Tx(tx) => Ok(Some(tx)),
+        }
+    }
+
+    /// Calls RPC node with retries, failed calls are repeated on the next selected endpoint
+    async fn request<T>(
+        &self,
+        method: Option<RpcMethod>,
+        call: impl for<'a> Fn(&'a RpcClient) -> BoxFuture<'a, ClientResult<T>>,
+    ) -> FetchingResult<T> {
+        let mut error_occured = 0;
+
+        loop {
+            if let Some(method) = method {
+                self.throttle(method).await;
+            }
+            let endpoint = self.rpc_pool.select();
+            let started_at = Instant::now();
+            let result = call(endpoint.client()).await;
+
+            endpoint.record(result.is_ok(), started_at.elapsed());
+            self.report.inc_metrics(endpoint.url(), &result);
+
+            match result {
+                Ok(value) => {
+                    self.report.set_available().await;
+                    return Ok(value);
                 }
                 Err(error) => {
                     self.report.set_unavailable().await;
-                    tracing::debug!(error = %error, "Couldn't fetch transaction");
+                    tracing::debug!(
+                        error = %error,
+                        endpoint = %endpoint.url(),
+                        "Rpc request failed"
+                    );
 
                     error_occured += 1;
-                    // Note: rpc has its own timeout - 30 sec
                     self.retry_delay(error_occured).await?;
                 }
             }
         }
     }
 
+    /// Returns the most recent slot of the configured commitment
+    #[tracing::instrument(level = "trace", skip(self))]
+    pub async fn get_slot(&self) -> FetchingResult<Slot> {
+        let commitment = self.commitment.commitment_config();
+
+        self.request(None, |client| {
+            client.get_slot_with_commitment(commitment).boxed()
+        })
+        .await
+    }
+
+    /// Returns the most recent finalized slot
+    #[tracing::instrument(level = "trace", skip(self))]
+    pub async fn get_finalized_slot(&self) -> FetchingResult<Slot> {
+        self.request(None, |client| {
+            client
+                .get_slot_with_commitment(CommitmentConfig::finalized())
+                .boxed()
+        })
+        .await
+    }
+
+    /// Returns blocks of the configured commitment between two slots, both inclusive
+    #[tracing::instrument(level = "trace", skip(self))]
+    pub async fn get_blocks(&self, start_slot: Slot, end_slot: Slot) -> FetchingResult<Vec<Slot>> {
+        let commitment = self.commitment.commitment_config();
+
+        self.request(None, |client| {
+            client
+                .get_blocks_with_commitment(start_slot, Some(end_slot), commitment)
+                .boxed()
+        })
+        .await
+    }
+
+    /// Returns commitment changes of transactions that were indexed at confirmed commitment.
+    ///
+    /// Finalized transactions are promoted, transactions that are unknown to the node
+    /// although their slot is already finalized are flagged as dropped.
+    #[tracing::instrument(level = "trace", skip(self, txs), fields(count = %txs.len()))]
+    pub async fn get_commitment_updates(
+        &self,
+        txs: &[(String, Slot)],
+    ) -> FetchingResult<Vec<(String, TxCommitment)>> {
+        let finalized_slot = self.get_finalized_slot().await?;
+        let mut updates = vec![];
+
+        for chunk in txs.chunks(MAX_SIGNATURE_STATUSES) {
+            let signatures = chunk
+                .iter()
+                .map(|(hash, _)| Signature::from_str(hash))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let statuses = self
+                .request(None, |client| {
+                    let signatures = signatures.clone();
+                    async move {
+                        client
+                            .get_signature_statuses_with_history(&signatures)
+                            .await
+                    }
+                    .boxed()
+                })
+                .await?
+                .value;
+
+            for ((hash, slot), status) in chunk.iter().zip(statuses) {
+                let commitment = match status {
+                    Some(status)
+                        if status.confirmation_status
+                            == Some(TransactionConfirmationStatus::Finalized)
+                            || status.confirmations.is_none() =>
+                    {
+                        TxCommitment::Finalized
+                    }
+                    None if *slot <= finalized_slot => TxCommitment::Dropped,
+                    _ => continue,
+                };
+
+                updates.push((hash.clone(), commitment));
+            }
+        }
+
+        Ok(updates)
+    }
+
+    /// Returns list of transactions of the block that reference the program
+    #[tracing::instrument(level = "trace", skip(self))]
+    pub async fn fetch_block(&self, slot: Slot) -> IndexingResult<Vec<Tx>> {
+        let config = RpcBlockConfig {
+            encoding: Some(UiTransactionEncoding::Json),
+            transaction_details: Some(TransactionDetails::Full),
+            rewards: Some(false),
+            commitment: Some(self.commitment.commitment_config()),
+            max_supported_transaction_version: Some(0),
+        };
+
+        let block = self
+            .request(Some(RpcMethod::GetBlock), |client| {
+                client.get_block_with_config(slot, config).boxed()
+            })
+            .await?;
+        tracing::debug!("Fetched block");
+
+        let mut txs = vec![];
+        for transaction in block.transactions.unwrap_or_default() {
+            let raw_tx = EncodedConfirmedTransactionWithStatusMeta {
+                slot,
+                transaction,
+                block_time: block.block_time,
+            };
+
+            let Some(sign) = self.get_program_tx_signature(&raw_tx) else {
+                continue;
+            };
+
+            if let Executor::Executor(ref e) = self.executor {
+                let mut executor = e.lock().await;
+                let control_flow = executor.process_signature(&sign).await?;
+                match control_flow {
+                    ExecutorControlFlow::Skip => continue,
+                    ExecutorControlFlow::Pass => (),
+                    ExecutorControlFlow::Stop => break,
+                };
+            }
+
+            if self.db_manager.recorded_tx(&sign.signature).await? {
+                continue;
+            }
+
+            let data = self.compress_if_stored(&raw_tx)?;
+            match self.handle_raw_tx(raw_tx).await? {
+                RawTxOutcome::Skip => continue,
+                RawTxOutcome::Stop(result) => txs.push(result?),
+                RawTxOutcome::Tx(tx) => {
+                    self.insert_tx(&tx, data).await?;
+                    txs.push(tx);
+                }
+            }
+        }
+
+        Ok(txs)
+    }
+
+    /// Returns signature status of the block transaction if it references the program
+    fn get_program_tx_signature(
+        &self,
+        raw_tx: &EncodedConfirmedTransactionWithStatusMeta,
+    ) -> Option<RpcConfirmedTransactionStatusWithSignature> {
+        let EncodedTransaction::Json(tx) = &raw_tx.transaction.transaction else {
+            return None;
+        };
+        let UiMessage::Raw(msg) = &tx.message else {
+            return None;
+        };
+
+        let program_id = self.program_id.to_string();
+        let meta = raw_tx.transaction.meta.as_ref();
+        let mentioned = msg.account_keys.contains(&program_id)
+            || matches!(
+                meta.map(|meta| meta.loaded_addresses.as_ref()),
+                Some(OptionSerializer::Some(loaded))
+                    if loaded.writable.contains(&program_id) || loaded.readonly.contains(&program_id)
+            );
+
+        if !mentioned {
+            return None;
+        }
+
+        Some(RpcConfirmedTransactionStatusWithSignature {
+            signature: tx.signatures.first()?.clone(),
+            slot: raw_tx.slot,
+            err: meta.and_then(|meta| meta.err.clone()),
+            memo: None,
+            block_time: raw_tx.block_time,
+            confirmation_status: None,
+        })
+    }
+
     /// Creates single transaction
     #[tracing::instrument(level = "trace", skip(self, confirmed_tx))]
     pub(crate) async fn create_tx(
@@ -336,7 +762,7 @@ where
             return Err(NativeFetchingError::TxWithoutAccounts.into());
         }
 
-        let account_keys = msg
+        let mut account_keys = msg
             .account_keys
             .iter()
             .enumerate()
@@ -346,7 +772,28 @@ where
                 signer: is_acc_signer(index, &msg),
                 source: None,
             })
-            .collect();
+            .collect::<Vec<_>>();
+
+        // Versioned transactions address the accounts loaded from lookup tables after the static ones
+        if let Some(OptionSerializer::Some(loaded)) = confirmed_tx
+            .transaction
+            .meta
+            .as_ref()
+            .map(|meta| &meta.loaded_addresses)
+        {
+            let loaded_accounts = loaded
+                .writable
+                .iter()
+                .map(|pubkey| (pubkey, true))
+                .chain(loaded.readonly.iter().map(|pubkey| (pubkey, false)));
+
+            account_keys.extend(loaded_accounts.map(|(pubkey, writable)| ParsedAccount {
+                pubkey: pubkey.clone(),
+                writable,
+                signer: false,
+                source: Some(ParsedAccountSource::LookupTable),
+            }));
+        }
 
         let_extract!(
             Some(hash),
@@ -359,7 +806,14 @@ where
             return Err(NativeFetchingError::TxWithoutBlocktime.into())
         );
 
-        let tx = Tx::new(hash.clone(), blocktime, msg.instructions, account_keys);
+        let mut tx = Tx::new(
+            hash.clone(),
+            confirmed_tx.slot,
+            blocktime,
+            msg.instructions,
+            account_keys,
+        );
+        tx.commitment = self.commitment.initial_tx_commitment();
 
         Ok(tx)
     }
@@ -1,7 +1,10 @@
 use serde::Deserialize;
 
 /// Maximum amount of transaction that can be fetched from RPC node
-const MAX_TRANSACTION_BATCH_SIZE: usize = 20;
+pub const MAX_TRANSACTION_BATCH_SIZE: usize = 1000;
+
+/// Amount of transaction fetched at once by default
+const DEFAULT_TRANSACTION_BATCH_SIZE: usize = 20;
 
 /// Settings struct dedicated to fetching data from Solana RPC
 #[derive(Deserialize, Clone, Debug)]
@@ -14,6 +17,17 @@ pub struct FetchingSettings {
 
     /// Amount of transaction that can be fetched in one time
     pub transaction_batch_size: usize,
+
+    /// Client-side rate limits of RPC methods
+    pub rate_limits: Option<RateLimitSettings>,
+
+    /// The way transactions are discovered (default - signatures)
+    #[serde(default)]
+    pub strategy: FetchingStrategy,
+
+    /// Whether to keep compressed fetched transactions for reindexing (default - false)
+    #[serde(default)]
+    pub store_raw_transactions: bool,
 }
 
 impl Default for FetchingSettings {
@@ -21,7 +35,46 @@ impl Default for FetchingSettings {
         FetchingSettings {
             rpc_request_timeout: 100,
             retry_limit: 10,
-            transaction_batch_size: MAX_TRANSACTION_BATCH_SIZE,
+            transaction_batch_size: DEFAULT_TRANSACTION_BATCH_SIZE,
+            rate_limits: None,
+            strategy: FetchingStrategy::default(),
+            store_raw_transactions: false,
         }
     }
 }
+
+/// Strategy of discovering program transactions
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FetchingStrategy {
+    /// Pages `getSignaturesForAddress` and fetches every transaction with `getTransaction`
+    #[default]
+    Signatures,
+
+    /// Walks confirmed slots with `getBlocks` and reads whole blocks with `getBlock`,
+    /// which is cheaper for programs with very high transaction volume
+    Blocks,
+}
+
+/// Per-method rate limits of RPC calls, methods without a limit are not throttled
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct RateLimitSettings {
+    /// Limit of `getSignaturesForAddress` calls
+    pub get_signatures_for_address: Option<MethodRateLimit>,
+
+    /// Limit of `getTransaction` calls
+    pub get_transaction: Option<MethodRateLimit>,
+
+    /// Limit of `getBlock` calls
+    pub get_block: Option<MethodRateLimit>,
+}
+
+/// Token bucket parameters of a single RPC method
+#[derive(Deserialize, Clone, Debug)]
+pub struct MethodRateLimit {
+    /// Amount of calls allowed per second
+    pub requests_per_second: f64,
+
+    /// Amount of calls that can be made at once after an idle period (default - 1)
+    pub burst: Option<u32>,
+}
@@ -0,0 +1,129 @@
+use {
+    crate::utils::fibonacci,
+    futures::StreamExt,
+    solana_client::{
+        nonblocking::pubsub_client::{PubsubClient, PubsubClientError},
+        rpc_config::{RpcTransactionLogsConfig, RpcTransactionLogsFilter},
+        rpc_response::RpcConfirmedTransactionStatusWithSignature,
+    },
+    solana_sdk::commitment_config::CommitmentConfig,
+    solana_transaction_status::TransactionConfirmationStatus,
+    std::time::Duration,
+    tokio::{sync::mpsc::Sender, task::JoinHandle, time::sleep},
+    tracing::{debug, info, warn},
+};
+
+/// Base delay in milliseconds between reconnection attempts
+const RECONNECT_DELAY: u64 = 500;
+
+/// Maximum number of reconnection attempts taken into account by the backoff
+const MAX_RECONNECT_BACKOFF: u64 = 10;
+
+/// Outcome of a single subscription session
+enum SessionEnd {
+    /// Notification stream was closed by the node
+    Disconnected,
+
+    /// Nobody listens to the notifications anymore
+    ReceiverDropped,
+}
+
+/// Subscribes to `logsSubscribe` notifications mentioning the program
+/// and forwards signatures of the notified transactions
+#[derive(Clone, Debug)]
+pub struct LogSubscriber {
+    /// A WebSocket URL of working environment
+    ws_connection_str: String,
+
+    /// The public key of the account containing a program
+    program_id: String,
+
+    /// Commitment level of notifications
+    commitment: CommitmentConfig,
+}
+
+impl LogSubscriber {
+    pub fn new(
+        ws_connection_str: String,
+        program_id: String,
+        commitment: CommitmentConfig,
+    ) -> Self {
+        Self {
+            ws_connection_str,
+            program_id,
+            commitment,
+        }
+    }
+
+    /// Spawns a task that keeps the subscription alive until the receiver is dropped
+    pub fn spawn(
+        self,
+        sender: Sender<RpcConfirmedTransactionStatusWithSignature>,
+    ) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut failures = 0;
+
+            loop {
+                match self.subscribe(&sender).await {
+                    Ok(SessionEnd::ReceiverDropped) => break,
+                    Ok(SessionEnd::Disconnected) => {
+                        info!("Logs subscription closed, reconnecting");
+                        failures = 0;
+                    }
+                    Err(error) => {
+                        warn!(error = %error, "Logs subscription failed");
+                        failures = (failures + 1).min(MAX_RECONNECT_BACKOFF);
+                    }
+                }
+
+                sleep(Duration::from_millis(RECONNECT_DELAY * fibonacci(failures))).await;
+            }
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip(self, sender), fields(program_id = %self.program_id))]
+    async fn subscribe(
+        &self,
+        sender: &Sender<RpcConfirmedTransactionStatusWithSignature>,
+    ) -> Result<SessionEnd, PubsubClientError> {
+        let client = PubsubClient::new(&self.ws_connection_str).await?;
+        let (mut notifications, unsubscribe) = client
+            .logs_subscribe(
+                RpcTransactionLogsFilter::Mentions(vec![self.program_id.clone()]),
+                RpcTransactionLogsConfig {
+                    commitment: Some(self.commitment),
+                },
+            )
+            .await?;
+        info!("Subscribed to program logs");
+
+        let mut session_end = SessionEnd::Disconnected;
+        while let Some(notification) = notifications.next().await {
+            debug!(signature = %notification.value.signature, "Received logs notification");
+
+            let signature = RpcConfirmedTransactionStatusWithSignature {
+                signature: notification.value.signature,
+                slot: notification.context.slot,
+                err: notification.value.err,
+                memo: None,
+                block_time: None,
+                confirmation_status: Some(if self.commitment.is_finalized() {
+                    TransactionConfirmationStatus::Finalized
+                } else {
+                    TransactionConfirmationStatus::Confirmed
+                }),
+            };
+
+            if sender.send(signature).await.is_err() {
+                session_end = SessionEnd::ReceiverDropped;
+                break;
+            }
+        }
+
+        drop(notifications);
+        unsubscribe().await;
+        client.shutdown().await?;
+
+        Ok(session_end)
+    }
+}
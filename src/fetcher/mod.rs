@@ -1,6 +1,9 @@
 pub mod fetcher_error;
 pub mod fetching_manager;
 pub mod fetching_settings;
+pub mod log_subscriber;
+pub mod rate_limiter;
+pub mod rpc_pool;
 pub mod tx;
 
 #[cfg(test)]
@@ -9,4 +12,7 @@ mod tests;
 pub use fetcher_error::*;
 pub use fetching_manager::*;
 pub use fetching_settings::*;
+pub use log_subscriber::*;
+pub use rate_limiter::*;
+pub use rpc_pool::*;
 pub use tx::*;
@@ -0,0 +1,130 @@
+use {
+    crate::fetcher::{MethodRateLimit, RateLimitSettings},
+    std::{
+        collections::HashMap,
+        fmt,
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    },
+    tokio::time::sleep,
+};
+
+/// RPC methods that are subject to client-side rate limiting
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum RpcMethod {
+    GetSignaturesForAddress,
+    GetTransaction,
+    GetBlock,
+}
+
+impl RpcMethod {
+    /// Returns the JSON RPC name of the method
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RpcMethod::GetSignaturesForAddress => "getSignaturesForAddress",
+            RpcMethod::GetTransaction => "getTransaction",
+            RpcMethod::GetBlock => "getBlock",
+        }
+    }
+}
+
+impl fmt::Display for RpcMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Token bucket state of a single RPC method
+#[derive(Debug)]
+struct TokenBucket {
+    /// Maximum amount of tokens that can be accumulated
+    capacity: f64,
+
+    /// Amount of tokens restored per second
+    refill_rate: f64,
+
+    /// Currently available tokens, negative value means queued reservations
+    tokens: f64,
+
+    /// Last time the bucket was refilled
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(limit: &MethodRateLimit) -> Self {
+        let capacity = limit.burst.unwrap_or(1).max(1) as f64;
+
+        Self {
+            capacity,
+            refill_rate: limit.requests_per_second,
+            tokens: capacity,
+            updated_at: Instant::now(),
+        }
+    }
+
+    /// Reserves a token and returns the time to wait until it becomes available
+    fn reserve(&mut self, now: Instant) -> Duration {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_rate).min(self.capacity);
+        self.updated_at = now;
+        self.tokens -= 1.0;
+
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.refill_rate)
+        }
+    }
+}
+
+/// A client-side token bucket limiter for RPC calls.
+///
+/// Clones share the same buckets, so one limiter can throttle any number of concurrent fetches.
+#[derive(Debug, Clone, Default)]
+pub struct RateLimiter {
+    buckets: Arc<HashMap<RpcMethod, Mutex<TokenBucket>>>,
+}
+
+impl RateLimiter {
+    pub fn new(settings: &RateLimitSettings) -> Self {
+        let buckets = [
+            (
+                RpcMethod::GetSignaturesForAddress,
+                &settings.get_signatures_for_address,
+            ),
+            (RpcMethod::GetTransaction, &settings.get_transaction),
+            (RpcMethod::GetBlock, &settings.get_block),
+        ]
+        .into_iter()
+        .filter_map(|(method, limit)| {
+            limit
+                .as_ref()
+                .filter(|limit| limit.requests_per_second > 0.0)
+                .map(|limit| (method, Mutex::new(TokenBucket::new(limit))))
+        })
+        .collect();
+
+        Self {
+            buckets: Arc::new(buckets),
+        }
+    }
+
+    /// Waits until the call of the method is allowed and returns the time spent waiting
+    pub async fn acquire(&self, method: RpcMethod) -> Duration {
+        let Some(bucket) = self.buckets.get(&method) else {
+            return Duration::ZERO;
+        };
+
+        let delay = bucket
+            .lock()
+            .expect("Rate limiter lock is poisoned")
+            .reserve(Instant::now());
+
+        if !delay.is_zero() {
+            tracing::trace!(method = %method, delay = ?delay, "Rpc call throttled");
+            sleep(delay).await;
+        }
+
+        delay
+    }
+}
@@ -0,0 +1,230 @@
+use {
+    crate::RpcEndpointSettings,
+    solana_client::nonblocking::rpc_client::RpcClient,
+    solana_sdk::commitment_config::CommitmentConfig,
+    std::{
+        sync::{Mutex, MutexGuard},
+        time::{Duration, Instant},
+    },
+    tracing::{info, warn},
+};
+
+/// Number of consecutive failures after which endpoint is considered unhealthy
+const FAILURE_THRESHOLD: u32 = 3;
+
+/// Time after which an unhealthy endpoint is probed again
+const PROBE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Smoothing factor of the moving averages of error rate and latency
+const EWMA_ALPHA: f64 = 0.2;
+
+/// Minimal share of the weight left to an endpoint with high error rate
+const MIN_HEALTH_FACTOR: f64 = 0.05;
+
+/// Health statistics of a single endpoint
+#[derive(Debug, Default)]
+struct EndpointHealth {
+    /// Moving average of failed calls share
+    error_rate: f64,
+
+    /// Moving average of call latency in seconds
+    latency: f64,
+
+    /// Number of failed calls in a row
+    consecutive_failures: u32,
+
+    /// Time when an unhealthy endpoint can be probed again
+    probe_at: Option<Instant>,
+
+    /// Accumulated weight of smooth weighted round-robin selection
+    current_weight: f64,
+}
+
+impl EndpointHealth {
+    fn is_available(&self, now: Instant) -> bool {
+        self.probe_at.map_or(true, |probe_at| probe_at <= now)
+    }
+
+    fn effective_weight(&self, weight: u32) -> f64 {
+        weight as f64 * (1.0 - self.error_rate).max(MIN_HEALTH_FACTOR)
+    }
+}
+
+/// A single RPC node with its health statistics
+pub struct RpcEndpoint {
+    /// An HTTP URL of the node
+    url: String,
+
+    /// A client of the node
+    client: RpcClient,
+
+    /// Endpoints with lower priority value are preferred
+    priority: u32,
+
+    /// Share of requests among endpoints of the same priority
+    weight: u32,
+
+    /// Health statistics
+    health: Mutex<EndpointHealth>,
+}
+
+impl RpcEndpoint {
+    fn new(url: String, client: RpcClient, priority: u32, weight: u32) -> Self {
+        Self {
+            url,
+            client,
+            priority,
+            weight: weight.max(1),
+            health: Mutex::new(EndpointHealth::default()),
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn client(&self) -> &RpcClient {
+        &self.client
+    }
+
+    fn health(&self) -> MutexGuard<'_, EndpointHealth> {
+        self.health
+            .lock()
+            .expect("Endpoint health lock is poisoned")
+    }
+
+    /// Updates health statistics with the outcome of a call
+    pub fn record(&self, success: bool, latency: Duration) {
+        let mut health = self.health();
+        let failure = if success { 0.0 } else { 1.0 };
+
+        health.error_rate += EWMA_ALPHA * (failure - health.error_rate);
+        health.latency += EWMA_ALPHA * (latency.as_secs_f64() - health.latency);
+
+        if success {
+            if health.probe_at.take().is_some() {
+                info!(endpoint = %self.url, "Rpc endpoint recovered");
+            }
+            health.consecutive_failures = 0;
+        } else {
+            health.consecutive_failures += 1;
+
+            if health.consecutive_failures >= FAILURE_THRESHOLD {
+                if health.probe_at.is_none() {
+                    warn!(
+                        endpoint = %self.url,
+                        error_rate = health.error_rate,
+                        latency = health.latency,
+                        "Rpc endpoint marked unhealthy"
+                    );
+                }
+                health.probe_at = Some(Instant::now() + PROBE_INTERVAL);
+            }
+        }
+    }
+}
+
+/// A set of RPC endpoints with failover between them
+pub struct RpcPool {
+    endpoints: Vec<RpcEndpoint>,
+}
+
+impl RpcPool {
+    pub fn new(
+        connection_str: &str,
+        endpoints: &[RpcEndpointSettings],
+        timeout: Duration,
+        commitment: CommitmentConfig,
+    ) -> Self {
+        let primary = RpcClient::new_with_timeout_and_commitment(
+            connection_str.to_string(),
+            timeout,
+            commitment,
+        );
+
+        Self::with_client(primary, endpoints, timeout, commitment)
+    }
+
+    /// Creates pool around a prepared primary client, additional endpoints get clients of their own
+    pub fn with_client(
+        primary: RpcClient,
+        endpoints: &[RpcEndpointSettings],
+        timeout: Duration,
+        commitment: CommitmentConfig,
+    ) -> Self {
+        let mut pool = vec![RpcEndpoint::new(primary.url(), primary, 0, 1)];
+        pool.extend(endpoints.iter().map(|endpoint| {
+            RpcEndpoint::new(
+                endpoint.url.clone(),
+                RpcClient::new_with_timeout_and_commitment(
+                    endpoint.url.clone(),
+                    timeout,
+                    commitment,
+                ),
+                endpoint.priority.unwrap_or(1),
+                endpoint.weight.unwrap_or(1),
+            )
+        }));
+
+        Self { endpoints: pool }
+    }
+
+    /// Creates pool from a single prepared client
+    #[cfg(test)]
+    pub fn from_client(url: String, client: RpcClient) -> Self {
+        Self {
+            endpoints: vec![RpcEndpoint::new(url, client, 0, 1)],
+        }
+    }
+
+    /// Selects an endpoint for the next call.
+    ///
+    /// Healthy endpoints of the best priority share calls by weight reduced by their error rate.
+    /// Unhealthy endpoints are skipped until their probe time comes.
+    pub fn select(&self) -> &RpcEndpoint {
+        let now = Instant::now();
+        let available = self
+            .endpoints
+            .iter()
+            .filter(|endpoint| endpoint.health().is_available(now))
+            .collect::<Vec<_>>();
+
+        let Some(priority) = available.iter().map(|endpoint| endpoint.priority).min() else {
+            // All endpoints are unhealthy, so the one to be probed first is used
+            return self
+                .endpoints
+                .iter()
+                .min_by_key(|endpoint| endpoint.health().probe_at)
+                .expect("Rpc pool is empty");
+        };
+
+        let candidates = available
+            .into_iter()
+            .filter(|endpoint| endpoint.priority == priority)
+            .collect::<Vec<_>>();
+
+        let mut total_weight = 0.0;
+        let mut selected: Option<(&RpcEndpoint, f64)> = None;
+        for endpoint in candidates {
+            let mut health = endpoint.health();
+            let weight = health.effective_weight(endpoint.weight);
+            health.current_weight += weight;
+            total_weight += weight;
+
+            if selected.map_or(true, |(_, current)| health.current_weight > current) {
+                selected = Some((endpoint, health.current_weight));
+            }
+        }
+
+        let (endpoint, _) = selected.expect("Rpc pool is empty");
+        let mut health = endpoint.health();
+        health.current_weight -= total_weight;
+        if health.probe_at.is_some() {
+            // Postpone next probe so that only one call checks the endpoint
+            health.probe_at = Some(now + PROBE_INTERVAL);
+            info!(endpoint = %endpoint.url, "Probing unhealthy rpc endpoint");
+        }
+
+        endpoint
+    }
+}
@@ -3,6 +3,7 @@ use {
     crate::{
         db::{test_connection_manager::ConnectionManager, DbManager},
         indexer::IndexerReport,
+        ErrorCategory, IndexerError, IndexingResult, RpcEndpointSettings,
     },
     enum_extract::let_extract,
     solana_client::rpc_response::RpcConfirmedTransactionStatusWithSignature,
@@ -26,7 +27,7 @@ async fn get_signatures(url: String) -> FetchingResult<TxBatch> {
     fetching_manager.get_signatures(&None, &None).await
 }
 
-async fn fetch_batch(url: String) -> FetchingResult<Vec<Tx>> {
+async fn fetch_batch(url: String) -> IndexingResult<Vec<Tx>> {
     let connection_manager = ConnectionManager::build().await;
     let fetching_manager = get_fetcher(url, connection_manager.get_connection_options()).await;
 
@@ -81,12 +82,19 @@ async fn fetch_batch_success_test() {
 
 #[tokio::test(flavor = "multi_thread")]
 async fn fetch_batch_fail_test() {
-    let_extract!(
-        FetchingError::NativeFetcher(err),
-        fetch_batch("fails".into()).await.unwrap_err(),
-        panic!("Wrong error type")
+    let error = fetch_batch("fails".into()).await.unwrap_err();
+
+    assert!(
+        matches!(
+            error.root(),
+            IndexerError::FetcherError(FetchingError::NativeFetcher(
+                NativeFetchingError::RpcCallLimit
+            ))
+        ),
+        "Wrong error type: {error}"
     );
-    assert_eq!(err, NativeFetchingError::RpcCallLimit);
+    assert_eq!(error.category(), ErrorCategory::Transient);
+    assert_eq!(error.context().and_then(|context| context.slot), Some(123));
 }
 
 #[tokio::test(flavor = "multi_thread")]
@@ -264,3 +272,266 @@ async fn create_tx_without_blocktime_test() {
     );
     assert_eq!(err, NativeFetchingError::TxWithoutBlocktime);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rate_limiter_burst_test() {
+    let rate_limiter = RateLimiter::new(&RateLimitSettings {
+        get_transaction: Some(MethodRateLimit {
+            requests_per_second: 20.0,
+            burst: Some(2),
+        }),
+        ..Default::default()
+    });
+
+    assert!(rate_limiter
+        .acquire(RpcMethod::GetTransaction)
+        .await
+        .is_zero());
+    assert!(rate_limiter
+        .acquire(RpcMethod::GetTransaction)
+        .await
+        .is_zero());
+
+    let wait = rate_limiter.acquire(RpcMethod::GetTransaction).await;
+    assert!(!wait.is_zero());
+    assert!(wait <= std::time::Duration::from_millis(50));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rate_limiter_unlimited_method_test() {
+    let rate_limiter = RateLimiter::new(&RateLimitSettings {
+        get_transaction: Some(MethodRateLimit {
+            requests_per_second: 1.0,
+            burst: None,
+        }),
+        ..Default::default()
+    });
+
+    for _ in 0..10 {
+        assert!(rate_limiter
+            .acquire(RpcMethod::GetSignaturesForAddress)
+            .await
+            .is_zero());
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rate_limiter_shared_test() {
+    let rate_limiter = RateLimiter::new(&RateLimitSettings {
+        get_block: Some(MethodRateLimit {
+            requests_per_second: 100.0,
+            burst: None,
+        }),
+        ..Default::default()
+    });
+    let shared_limiter = rate_limiter.clone();
+
+    assert!(rate_limiter.acquire(RpcMethod::GetBlock).await.is_zero());
+    assert!(!shared_limiter.acquire(RpcMethod::GetBlock).await.is_zero());
+}
+
+fn get_rpc_pool(endpoints: Vec<RpcEndpointSettings>) -> RpcPool {
+    RpcPool::new(
+        "http://primary",
+        &endpoints,
+        std::time::Duration::from_secs(1),
+        solana_sdk::commitment_config::CommitmentConfig::confirmed(),
+    )
+}
+
+#[test]
+fn rpc_pool_priority_test() {
+    let rpc_pool = get_rpc_pool(vec![RpcEndpointSettings {
+        url: "http://fallback".to_string(),
+        priority: None,
+        weight: None,
+    }]);
+
+    for _ in 0..5 {
+        assert_eq!(rpc_pool.select().url(), "http://primary");
+    }
+}
+
+#[test]
+fn rpc_pool_failover_test() {
+    let rpc_pool = get_rpc_pool(vec![RpcEndpointSettings {
+        url: "http://fallback".to_string(),
+        priority: None,
+        weight: None,
+    }]);
+
+    for _ in 0..3 {
+        let endpoint = rpc_pool.select();
+        assert_eq!(endpoint.url(), "http://primary");
+        endpoint.record(false, std::time::Duration::from_millis(10));
+    }
+
+    let endpoint = rpc_pool.select();
+    assert_eq!(endpoint.url(), "http://fallback");
+    endpoint.record(true, std::time::Duration::from_millis(10));
+    assert_eq!(rpc_pool.select().url(), "http://fallback");
+}
+
+#[test]
+fn rpc_pool_weight_test() {
+    let rpc_pool = get_rpc_pool(vec![RpcEndpointSettings {
+        url: "http://heavy".to_string(),
+        priority: Some(0),
+        weight: Some(3),
+    }]);
+
+    let heavy_calls = (0..8)
+        .filter(|_| rpc_pool.select().url() == "http://heavy")
+        .count();
+    assert_eq!(heavy_calls, 6);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_blocks_success_test() {
+    let connection_manager = ConnectionManager::build().await;
+    let fetching_manager = get_fetcher(
+        "succeeds".into(),
+        connection_manager.get_connection_options(),
+    )
+    .await;
+
+    let slots = fetching_manager.get_blocks(1, 3).await.unwrap();
+    assert_eq!(slots, vec![1, 2, 3]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_blocks_fail_test() {
+    let connection_manager = ConnectionManager::build().await;
+    let fetching_manager =
+        get_fetcher("fails".into(), connection_manager.get_connection_options()).await;
+
+    let_extract!(
+        FetchingError::NativeFetcher(err),
+        fetching_manager.get_blocks(1, 3).await.unwrap_err(),
+        panic!("Wrong error type")
+    );
+    assert_eq!(err, NativeFetchingError::RpcCallLimit);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn fetch_block_without_program_test() {
+    let connection_manager = ConnectionManager::build().await;
+    let fetching_manager = get_fetcher(
+        "succeeds".into(),
+        connection_manager.get_connection_options(),
+    )
+    .await;
+
+    let txs = fetching_manager.fetch_block(1).await.unwrap();
+    assert!(txs.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn program_tx_signature_test() {
+    let connection_manager = ConnectionManager::build().await;
+    let fetching_manager = get_fetcher(
+        "succeeds".into(),
+        connection_manager.get_connection_options(),
+    )
+    .await;
+
+    let raw_tx = |account_key: &str| {
+        EncodedConfirmedTransactionWithStatusMeta {
+        slot: 123,
+        transaction: EncodedTransactionWithStatusMeta {
+            transaction: EncodedTransaction::Json(UiTransaction {
+                signatures: vec!["3AsdoALgZFuq2oUVWrDYhg2pNeaLJKPLf8hU2mQ6U8qJxeJ6hsrPVpMn9ma39DtfYCrDQSvngWRP8NnTpEhezJpE".to_string()],
+                message: UiMessage::Raw(UiRawMessage {
+                    header: MessageHeader {
+                        num_required_signatures: 1,
+                        num_readonly_signed_accounts: 1,
+                        num_readonly_unsigned_accounts: 1,
+                    },
+                    account_keys: vec![account_key.to_string()],
+                    recent_blockhash: String::default(),
+                    instructions: vec![],
+                    address_table_lookups: None,
+                }),
+            }),
+            meta: None,
+            version: None,
+        },
+        block_time: Some(123),
+    }
+    };
+
+    let sign = fetching_manager
+        .get_program_tx_signature(&raw_tx("11111111111111111111111111111111"))
+        .expect("Transaction references the program");
+    assert_eq!(sign.slot, 123);
+    assert_eq!(sign.block_time, Some(123));
+
+    assert!(fetching_manager
+        .get_program_tx_signature(&raw_tx("SysvarC1ock11111111111111111111111111111111"))
+        .is_none());
+}
+
+#[test]
+fn raw_tx_compression_test() {
+    let raw_tx = EncodedConfirmedTransactionWithStatusMeta {
+        slot: 123,
+        transaction: EncodedTransactionWithStatusMeta {
+            transaction: EncodedTransaction::Json(UiTransaction {
+                signatures: vec!["3AsdoALgZFuq2oUVWrDYhg2pNeaLJKPLf8hU2mQ6U8qJxeJ6hsrPVpMn9ma39DtfYCrDQSvngWRP8NnTpEhezJpE".to_string()],
+                message: UiMessage::Raw(UiRawMessage {
+                    header: MessageHeader {
+                        num_required_signatures: 1,
+                        num_readonly_signed_accounts: 1,
+                        num_readonly_unsigned_accounts: 1,
+                    },
+                    account_keys: vec!["11111111111111111111111111111111".to_string()],
+                    recent_blockhash: String::default(),
+                    instructions: vec![],
+                    address_table_lookups: None,
+                }),
+            }),
+            meta: None,
+            version: None,
+        },
+        block_time: Some(123),
+    };
+
+    let data = compress_raw_tx(&raw_tx).expect("Failed to compress transaction");
+    assert_eq!(
+        decompress_raw_tx(&data).expect("Failed to decompress transaction"),
+        raw_tx
+    );
+    assert!(decompress_raw_tx(b"not a gzip stream").is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn commitment_updates_test() {
+    let connection_manager = ConnectionManager::build().await;
+    let signature =
+        "3AsdoALgZFuq2oUVWrDYhg2pNeaLJKPLf8hU2mQ6U8qJxeJ6hsrPVpMn9ma39DtfYCrDQSvngWRP8NnTpEhezJpE"
+            .to_string();
+
+    // The mock node reports every transaction as finalized
+    let fetching_manager = get_fetcher(
+        "succeeds".into(),
+        connection_manager.get_connection_options(),
+    )
+    .await;
+    let updates = fetching_manager
+        .get_commitment_updates(&[(signature.clone(), 5)])
+        .await
+        .unwrap();
+    assert_eq!(updates, vec![(signature.clone(), TxCommitment::Finalized)]);
+
+    // Unknown transactions are dropped only once their slot is finalized, the mock finalized slot is 0
+    let fetching_manager = get_fetcher(
+        "sig_not_found".into(),
+        connection_manager.get_connection_options(),
+    )
+    .await;
+    let updates = fetching_manager
+        .get_commitment_updates(&[(signature.clone(), 0), (signature.clone(), 5)])
+        .await
+        .unwrap();
+    assert_eq!(updates, vec![(signature, TxCommitment::Dropped)]);
+}
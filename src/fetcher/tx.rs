@@ -1,14 +1,22 @@
 use {
     chrono::Utc,
-    solana_sdk::clock::UnixTimestamp,
-    solana_transaction_status::{parse_accounts::ParsedAccount, UiCompiledInstruction},
-    std::fmt,
+    flate2::{read::GzDecoder, write::GzEncoder, Compression},
+    solana_sdk::clock::{Slot, UnixTimestamp},
+    solana_transaction_status::{
+        parse_accounts::ParsedAccount, EncodedConfirmedTransactionWithStatusMeta,
+        UiCompiledInstruction,
+    },
+    std::{fmt, io},
 };
 
+#[derive(Clone)]
 pub struct Tx {
     /// Transaction signature hash
     pub hash: String,
 
+    /// Slot of transaction block
+    pub slot: Slot,
+
     /// Time of transaction block
     pub blocktime: UnixTimestamp,
 
@@ -21,6 +29,9 @@ pub struct Tx {
     // Internal indexing status of transaction
     pub indexing_status: IndexingStatus,
 
+    /// Commitment level the transaction was observed at
+    pub commitment: TxCommitment,
+
     /// Timestamp when indexing was conducted
     pub indexing_timestamp: UnixTimestamp,
 }
@@ -28,16 +39,19 @@ pub struct Tx {
 impl Tx {
     pub fn new(
         hash: String,
+        slot: Slot,
         blocktime: UnixTimestamp,
         instructions: Vec<UiCompiledInstruction>,
         account_keys: Vec<ParsedAccount>,
     ) -> Tx {
         Self {
             hash,
+            slot,
             blocktime,
             instructions,
             account_keys,
             indexing_status: IndexingStatus::Pending,
+            commitment: TxCommitment::Finalized,
             indexing_timestamp: Utc::now().timestamp(),
         }
     }
@@ -52,15 +66,40 @@ impl fmt::Debug for Tx {
 
         f.debug_struct("Transaction")
             .field("hash", &self.hash)
+            .field("slot", &self.slot)
             .field("instruction_count", &self.instructions.len())
             .field("accounts", &accounts)
             .finish()
     }
 }
 
-#[derive(sqlx::Type, PartialEq, Eq, Debug)]
+#[derive(sqlx::Type, PartialEq, Eq, Debug, Clone, Copy)]
 #[sqlx(type_name = "tx_status", rename_all = "lowercase")]
 pub enum IndexingStatus {
     Pending,
     Indexed,
 }
+
+#[derive(sqlx::Type, PartialEq, Eq, Debug, Clone, Copy)]
+#[sqlx(type_name = "tx_commitment", rename_all = "lowercase")]
+pub enum TxCommitment {
+    /// Transaction is confirmed by supermajority and can still be rolled back
+    Confirmed,
+    /// Transaction is rooted on the canonical chain
+    Finalized,
+    /// Confirmed transaction that is no longer on the canonical chain
+    Dropped,
+}
+
+/// Encodes fetched transaction into gzip compressed JSON
+pub fn compress_raw_tx(raw_tx: &EncodedConfirmedTransactionWithStatusMeta) -> io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(vec![], Compression::default());
+    serde_json::to_writer(&mut encoder, raw_tx)?;
+
+    encoder.finish()
+}
+
+/// Decodes transaction stored by [`compress_raw_tx`]
+pub fn decompress_raw_tx(data: &[u8]) -> io::Result<EncodedConfirmedTransactionWithStatusMeta> {
+    Ok(serde_json::from_reader(GzDecoder::new(data))?)
+}
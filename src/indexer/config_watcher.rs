@@ -0,0 +1,232 @@
+use {
+    crate::{
+        configuration::{config_path, ConfigOverrides, Configuration},
+        db::DatabaseSettings,
+    },
+    secrecy::ExposeSecret,
+    std::{
+        path::{Path, PathBuf},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        time::SystemTime,
+    },
+    tracing::{info, warn},
+};
+
+/// Requests the indexer to reload its configuration at the next iteration boundary
+#[derive(Clone, Debug, Default)]
+pub struct ReloadHandle {
+    requested: Arc<AtomicBool>,
+}
+
+impl ReloadHandle {
+    /// Requests a reload
+    pub fn reload(&self) {
+        self.requested.store(true, Ordering::Relaxed);
+    }
+
+    fn take(&self) -> bool {
+        self.requested.swap(false, Ordering::Relaxed)
+    }
+}
+
+/// Watches the configuration file and reloads settings that can change without a restart
+pub struct ConfigWatcher {
+    /// Configuration the indexer currently runs with
+    current: Configuration,
+
+    /// Overrides the indexer was built with
+    overrides: ConfigOverrides,
+
+    /// Path of the configuration file
+    path: PathBuf,
+
+    /// Modification time of the configuration file at the last check
+    modified: Option<SystemTime>,
+
+    /// Reload requests from signals and the application
+    handle: ReloadHandle,
+}
+
+impl ConfigWatcher {
+    /// Watches the file named by `INDEXER_CFG`,
+    /// `current` is the configuration the indexer is built with
+    pub fn new(current: Configuration, overrides: ConfigOverrides) -> Self {
+        Self::with_path(config_path(), current, overrides)
+    }
+
+    /// Watches the configuration file at `path`
+    pub fn with_path(
+        path: impl Into<PathBuf>,
+        current: Configuration,
+        overrides: ConfigOverrides,
+    ) -> Self {
+        let path = path.into();
+        let handle = ReloadHandle::default();
+
+        #[cfg(unix)]
+        listen_hangup(handle.clone());
+
+        Self {
+            current,
+            overrides,
+            modified: modified(&path),
+            path,
+            handle,
+        }
+    }
+
+    pub fn handle(&self) -> ReloadHandle {
+        self.handle.clone()
+    }
+
+    /// Returns the reloaded configuration if the file was modified or a reload was requested
+    /// and the changes can be applied without a restart
+    pub fn poll(&mut self) -> Option<Configuration> {
+        let modified = modified(&self.path);
+        let file_changed = modified != self.modified;
+        if !self.handle.take() && !file_changed {
+            return None;
+        }
+        self.modified = modified;
+
+        let path = self.path.to_string_lossy();
+        let settings = match Configuration::load_from(&path, &self.overrides) {
+            Ok(settings) => settings,
+            Err(error) => {
+                warn!(error = %error, "Configuration reload failed");
+                return None;
+            }
+        };
+
+        if let Err(report) = settings.validate().into_result() {
+            warn!(error = %report, "Configuration reload rejected");
+            return None;
+        }
+
+        let changes = structural_changes(&self.current, &settings);
+        if !changes.is_empty() {
+            warn!(
+                fields = ?changes,
+                "Configuration reload rejected, changed fields require a restart"
+            );
+            return None;
+        }
+
+        info!("Configuration reloaded");
+        self.current = settings.clone();
+        Some(settings)
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+#[cfg(unix)]
+fn listen_hangup(handle: ReloadHandle) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    match signal(SignalKind::hangup()) {
+        Ok(mut hangup) => {
+            tokio::spawn(async move {
+                while hangup.recv().await.is_some() {
+                    info!("Configuration reload requested");
+                    handle.reload();
+                }
+            });
+        }
+        Err(error) => warn!(error = %error, "Failed to listen for reload signal"),
+    }
+}
+
+/// Returns fields whose changes require the indexer to be rebuilt
+pub fn structural_changes(current: &Configuration, new: &Configuration) -> Vec<&'static str> {
+    let (current_indexer, new_indexer) = (&current.indexer_settings, &new.indexer_settings);
+    let current_fetcher = current.fetcher_settings.clone().unwrap_or_default();
+    let new_fetcher = new.fetcher_settings.clone().unwrap_or_default();
+
+    [
+        (
+            "indexer_settings.program_id",
+            current_indexer.program_id != new_indexer.program_id,
+        ),
+        (
+            "indexer_settings.connection_str",
+            current_indexer.connection_str != new_indexer.connection_str,
+        ),
+        (
+            "indexer_settings.ws_connection_str",
+            current_indexer.ws_connection_str != new_indexer.ws_connection_str,
+        ),
+        (
+            "indexer_settings.rpc_timeout",
+            current_indexer.rpc_timeout != new_indexer.rpc_timeout,
+        ),
+        (
+            "indexer_settings.rpc_endpoints",
+            current_indexer.rpc_endpoints != new_indexer.rpc_endpoints,
+        ),
+        (
+            "indexer_settings.commitment",
+            current_indexer.commitment != new_indexer.commitment,
+        ),
+        (
+            "indexer_settings.start",
+            current_indexer.start != new_indexer.start,
+        ),
+        (
+            "indexer_settings.end",
+            current_indexer.end != new_indexer.end,
+        ),
+        (
+            "indexer_settings.processing_order",
+            current_indexer.processing_order != new_indexer.processing_order,
+        ),
+        (
+            "indexer_settings.supervisor",
+            current_indexer.supervisor != new_indexer.supervisor,
+        ),
+        (
+            "indexer_settings.leader_election",
+            current_indexer.leader_election != new_indexer.leader_election,
+        ),
+        (
+            "indexer_settings.backfill",
+            current_indexer.backfill != new_indexer.backfill,
+        ),
+        (
+            "indexer_settings.outbox",
+            current_indexer.outbox != new_indexer.outbox,
+        ),
+        (
+            "fetcher_settings.strategy",
+            current_fetcher.strategy != new_fetcher.strategy,
+        ),
+        (
+            "fetcher_settings.store_raw_transactions",
+            current_fetcher.store_raw_transactions != new_fetcher.store_raw_transactions,
+        ),
+        (
+            "db_settings",
+            !same_db_settings(&current.db_settings, &new.db_settings),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(field, changed)| changed.then_some(field))
+    .collect()
+}
+
+fn same_db_settings(current: &DatabaseSettings, new: &DatabaseSettings) -> bool {
+    current.username == new.username
+        && current.password.expose_secret() == new.password.expose_secret()
+        && current.port == new.port
+        && current.host == new.host
+        && current.database_name == new.database_name
+        && current.require_ssl == new.require_ssl
+        && current.ssl_root_cert == new.ssl_root_cert
+}
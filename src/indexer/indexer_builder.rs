@@ -0,0 +1,175 @@
+use {
+    crate::{
+        configuration::{
+            validate_db_settings, validate_fetcher_settings, validate_indexer_settings,
+            Configuration, ValidationReport,
+        },
+        db::{DatabaseSettings, DbManager},
+        executor::ExecutorCallback,
+        fetcher::{FetchingManager, FetchingSettings},
+        indexer::{
+            indexer_engine::{Indexer, IndexerEngine},
+            indexer_settings::IndexerSettings,
+            ConfigWatcher, IndexerReport, IndexingResult,
+        },
+        processor::ProcessingManager,
+    },
+    config::ConfigError,
+    solana_client::nonblocking::rpc_client::RpcClient,
+    sqlx::PgPool,
+};
+
+/// Constructs [Indexer] from configuration pieces and prepared components without reading
+/// the configuration file
+pub struct IndexerBuilder<E> {
+    /// General indexer configuration
+    indexer_settings: IndexerSettings,
+
+    /// Database configuration, not required if a pool is provided
+    db_settings: Option<DatabaseSettings>,
+
+    /// Configuration of the fetching process
+    fetcher_settings: Option<FetchingSettings>,
+
+    /// Pool of database connections created by the caller
+    pool: Option<PgPool>,
+
+    /// Client of the primary RPC endpoint created by the caller
+    rpc_client: Option<RpcClient>,
+
+    /// Entity for instruction processing
+    executor: Option<E>,
+
+    /// Indexer status report shared with the caller
+    report: Option<IndexerReport>,
+
+    /// Watcher of the configuration file applying changes without a restart
+    config_watcher: Option<ConfigWatcher>,
+}
+
+impl<E> IndexerBuilder<E>
+where
+    E: ExecutorCallback + Send + Sync + 'static,
+{
+    /// Creates new builder with the general indexer configuration
+    pub fn new(indexer_settings: IndexerSettings) -> Self {
+        Self {
+            indexer_settings,
+            db_settings: None,
+            fetcher_settings: None,
+            pool: None,
+            rpc_client: None,
+            executor: None,
+            report: None,
+            config_watcher: None,
+        }
+    }
+
+    /// Creates new builder with all sections of a loaded configuration
+    pub fn from_configuration(configuration: Configuration) -> Self {
+        Self {
+            db_settings: Some(configuration.db_settings),
+            fetcher_settings: configuration.fetcher_settings,
+            ..Self::new(configuration.indexer_settings)
+        }
+    }
+
+    /// Sets the database configuration
+    pub fn db_settings(mut self, db_settings: DatabaseSettings) -> Self {
+        self.db_settings = Some(db_settings);
+        self
+    }
+
+    /// Sets the configuration of the fetching process
+    pub fn fetcher_settings(mut self, fetcher_settings: FetchingSettings) -> Self {
+        self.fetcher_settings = Some(fetcher_settings);
+        self
+    }
+
+    /// Uses the pool for database interaction instead of connecting with `db_settings`
+    pub fn pool(mut self, pool: PgPool) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
+    /// Uses the client for the primary RPC endpoint instead of connecting to `connection_str`
+    pub fn rpc_client(mut self, rpc_client: RpcClient) -> Self {
+        self.rpc_client = Some(rpc_client);
+        self
+    }
+
+    /// Sets the entity for instruction processing
+    pub fn executor(mut self, executor: E) -> Self {
+        self.executor = Some(executor);
+        self
+    }
+
+    /// Uses the report for indexer status, so that the caller can observe it
+    pub fn report(mut self, report: IndexerReport) -> Self {
+        self.report = Some(report);
+        self
+    }
+
+    /// Enables hot reload, the indexer applies changes of the watched configuration file
+    /// at iteration boundaries. Changes of fields that require a restart are rejected.
+    pub fn config_watcher(mut self, config_watcher: ConfigWatcher) -> Self {
+        self.config_watcher = Some(config_watcher);
+        self
+    }
+
+    /// Validates the settings and constructs the indexer
+    pub fn build(self) -> IndexingResult<Indexer<E>> {
+        self.validate().into_result()?;
+
+        let (db_manager_1, db_manager_2, db_manager_3) = match (self.pool, &self.db_settings) {
+            (Some(pool), _) => (
+                DbManager::from_pool(pool.clone()),
+                DbManager::from_pool(pool.clone()),
+                DbManager::from_pool(pool),
+            ),
+            // Note: three different instances are used due to problems with async runtime in some k8s containers
+            (None, Some(db_settings)) => (
+                DbManager::connect(db_settings.with_db())?,
+                DbManager::connect(db_settings.with_db())?,
+                DbManager::connect(db_settings.with_db())?,
+            ),
+            (None, None) => return Err(ConfigError::NotFound("db_settings".to_string()).into()),
+        };
+        let report = self.report.unwrap_or_default();
+
+        let fetching_manager = FetchingManager::with_client(
+            &self.indexer_settings,
+            self.fetcher_settings,
+            self.rpc_client,
+            report.clone(),
+            db_manager_1,
+        )?;
+
+        let mut indexer = Indexer::from_parts(
+            &self.indexer_settings,
+            fetching_manager,
+            ProcessingManager::new(db_manager_2, report.clone()),
+            db_manager_3,
+            report,
+            self.config_watcher,
+        );
+
+        if let Some(executor) = self.executor {
+            indexer.set_executor(executor);
+        }
+
+        Ok(indexer)
+    }
+
+    /// Checks the settings the indexer is going to be built with
+    fn validate(&self) -> ValidationReport {
+        let mut report = validate_indexer_settings(&self.indexer_settings);
+        if let Some(fetcher_settings) = &self.fetcher_settings {
+            report.extend(validate_fetcher_settings(fetcher_settings));
+        }
+        if let (None, Some(db_settings)) = (&self.pool, &self.db_settings) {
+            report.extend(validate_db_settings(db_settings));
+        }
+        report
+    }
+}
@@ -0,0 +1,203 @@
+use {
+    crate::{
+        db::Cursor,
+        fetcher::TxBatch,
+        indexer::{IndexerError, IndexingResult},
+    },
+    std::sync::{Arc, Mutex},
+    tokio::{
+        sync::{watch, Notify},
+        time::{sleep_until, Instant},
+    },
+};
+
+/// Stage of the indexing process
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IndexerPhase {
+    /// Indexing is not started yet
+    #[default]
+    Idle,
+
+    /// Transactions are fetched and processed
+    Indexing,
+
+    /// Waiting for the next iteration
+    Waiting,
+
+    /// Suspended until resumed
+    Paused,
+
+    /// Waiting for a restart after a transient error
+    Restarting,
+
+    /// Waiting until the indexing lease of another replica expires
+    Standby,
+
+    /// Indexing is finished
+    Stopped,
+}
+
+/// A batch of transactions the indexer works on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchProgress {
+    /// Signature of the first transaction of the batch
+    pub first_signature: String,
+
+    /// Signature of the last transaction of the batch
+    pub last_signature: String,
+
+    /// Amount of transactions in the batch
+    pub count: usize,
+}
+
+/// Current state of a running indexer
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IndexerSnapshot {
+    /// Stage of the indexing process
+    pub phase: IndexerPhase,
+
+    /// Position the next iteration continues from
+    pub cursor: Option<Cursor>,
+
+    /// Batch in progress
+    pub batch: Option<BatchProgress>,
+
+    /// Whether a pause is requested
+    pub paused: bool,
+
+    /// Whether a stop is requested
+    pub stop_requested: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Commands {
+    paused: bool,
+    stopped: bool,
+}
+
+struct ControlState {
+    commands: watch::Sender<Commands>,
+    trigger: Notify,
+    snapshot: Mutex<IndexerSnapshot>,
+}
+
+/// A cloneable handle driving a running indexer.
+/// Commands take effect at batch and iteration boundaries.
+#[derive(Clone)]
+pub struct IndexerControl {
+    state: Arc<ControlState>,
+}
+
+impl Default for IndexerControl {
+    fn default() -> Self {
+        Self {
+            state: Arc::new(ControlState {
+                commands: watch::Sender::new(Commands::default()),
+                trigger: Notify::new(),
+                snapshot: Mutex::default(),
+            }),
+        }
+    }
+}
+
+impl IndexerControl {
+    /// Suspends the indexing before the next batch
+    pub fn pause(&self) {
+        self.state
+            .commands
+            .send_modify(|commands| commands.paused = true);
+    }
+
+    /// Continues a paused indexing
+    pub fn resume(&self) {
+        self.state
+            .commands
+            .send_modify(|commands| commands.paused = false);
+    }
+
+    /// Stops the indexing once the current batch is processed
+    pub fn stop(&self) {
+        self.state
+            .commands
+            .send_modify(|commands| commands.stopped = true);
+    }
+
+    /// Starts the next iteration without waiting for the indexing interval
+    pub fn trigger(&self) {
+        self.state.trigger.notify_one();
+    }
+
+    /// Returns the current state of the indexer
+    pub fn snapshot(&self) -> IndexerSnapshot {
+        let commands = *self.state.commands.borrow();
+        IndexerSnapshot {
+            paused: commands.paused,
+            stop_requested: commands.stopped,
+            ..self.lock().clone()
+        }
+    }
+
+    /// Whether a stop is requested
+    pub fn is_stopped(&self) -> bool {
+        self.state.commands.borrow().stopped
+    }
+
+    /// Waits while the indexing is paused, fails with [IndexerError::Stopped] if a stop is requested
+    pub(crate) async fn checkpoint(&self) -> IndexingResult<()> {
+        let mut commands = self.state.commands.subscribe();
+        if commands.borrow().paused {
+            let phase = self.lock().phase;
+            self.set_phase(IndexerPhase::Paused);
+            // The sender is kept by the handle, so the wait never fails
+            let _ = commands
+                .wait_for(|commands| !commands.paused || commands.stopped)
+                .await;
+            self.set_phase(phase);
+        }
+
+        if self.is_stopped() {
+            return Err(IndexerError::Stopped);
+        }
+        Ok(())
+    }
+
+    /// Sleeps until the deadline, an iteration trigger or a stop request
+    pub(crate) async fn wait_until(&self, deadline: Instant) {
+        let mut commands = self.state.commands.subscribe();
+        tokio::select! {
+            _ = sleep_until(deadline) => (),
+            _ = self.state.trigger.notified() => (),
+            _ = commands.wait_for(|commands| commands.stopped) => (),
+        }
+    }
+
+    pub(crate) fn set_phase(&self, phase: IndexerPhase) {
+        self.lock().phase = phase;
+    }
+
+    pub(crate) fn set_cursor(&self, cursor: Option<Cursor>) {
+        self.lock().cursor = cursor;
+    }
+
+    pub(crate) fn set_batch(&self, batch: &TxBatch) {
+        self.lock().batch = match (batch.first(), batch.last()) {
+            (Some(first), Some(last)) => Some(BatchProgress {
+                first_signature: first.signature.clone(),
+                last_signature: last.signature.clone(),
+                count: batch.len(),
+            }),
+            _ => None,
+        };
+    }
+
+    pub(crate) fn finish_batch(&self) {
+        self.lock().batch = None;
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, IndexerSnapshot> {
+        self.state
+            .snapshot
+            .lock()
+            .expect("Indexer snapshot lock is poisoned")
+    }
+}
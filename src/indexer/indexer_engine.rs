@@ -1,19 +1,60 @@
 use futures::Future;
 
 use crate::{
-    configuration::{get_configuration, Configuration},
-    db::{DbManager, IndexerDbRecording},
-    executor::{Executor, ExecutorCallback},
-    fetcher::{FetchingManager, TxBatch},
-    indexer::{IndexerReport, IndexingResult},
-    processor::ProcessingManager,
+    configuration::{ConfigOverrides, Configuration},
+    db::{BackfillStatus, BackfillUnit, Cursor, DbManager, IndexerDbRecording},
+    executor::{Executor, ExecutorCallback, TxSignature},
+    fetcher::{FetchingManager, FetchingSettings, FetchingStrategy, LogSubscriber, TxBatch},
+    indexer::{
+        indexer_settings::{
+            BackfillSettings, IndexerSettings, IndexingBound, OutboxSettings, ProcessingOrder,
+            SupervisorSettings,
+        },
+        leader_election::default_holder,
+        ConfigWatcher, IndexerBuilder, IndexerControl, IndexerError, IndexerPhase, IndexerReport,
+        IndexerRole, IndexerStatus, IndexingResult, LeaderElection, ReloadHandle, Supervisor,
+    },
+    processor::{OutboxDelivery, OutboxDispatcher, ProcessingManager},
 };
 
+#[cfg(feature = "webhook")]
+use crate::sink::{WebhookClient, WebhookSettings};
+
 use {
-    chrono::Utc, solana_sdk::clock::UnixTimestamp, std::time::Duration, tokio::time::sleep,
-    tracing::info,
+    chrono::Utc,
+    solana_sdk::clock::{Slot, UnixTimestamp},
+    std::time::Duration,
+    tokio::{
+        sync::mpsc::{self, Receiver},
+        time::Instant,
+    },
+    tracing::{info, warn},
 };
 
+/// Maximum amount of signatures buffered from the logs subscription
+const LOG_NOTIFICATIONS_CAPACITY: usize = 1024;
+
+/// Amount of slots requested at once by the block-based ingestion
+const BLOCK_RANGE_SIZE: Slot = 100;
+
+/// Name of the cursor of the block-based ingestion
+const BLOCKS_CURSOR: &str = "blocks";
+
+/// Maximum amount of confirmed transactions checked for finalization per iteration
+const FINALIZATION_BATCH_SIZE: i64 = 1024;
+
+/// Name of the cursor of the oldest-first processing
+const SIGNATURES_CURSOR: &str = "signatures";
+
+/// Name of the cursor marking the completely collected signatures of an iteration
+const PENDING_CURSOR: &str = "pending_signatures";
+
+/// Amount of pending signatures processed at once
+const PENDING_BATCH_SIZE: i64 = 100;
+
+/// Amount of stored transactions replayed at once
+const REINDEX_BATCH_SIZE: i64 = 100;
+
 /// Indexer state
 pub struct Indexer<E> {
     /// Responsible for fetching data from rpc node
@@ -33,36 +74,125 @@ pub struct Indexer<E> {
 
     /// Whether to perform database migration on start
     migrate: bool,
+
+    /// Whether to track confirmed transactions until finalization
+    track_finalization: bool,
+
+    /// The oldest point of history to index
+    start: Option<IndexingBound>,
+
+    /// The newest point of history to index, indexing stops once it is reached
+    end: Option<IndexingBound>,
+
+    /// Order in which signatures of an iteration are processed
+    processing_order: ProcessingOrder,
+
+    /// Source of real-time transaction notifications
+    log_subscriber: Option<LogSubscriber>,
+
+    /// Signatures received from the logs subscription
+    log_receiver: Option<Receiver<TxSignature>>,
+
+    /// Source of configuration changes applied without a restart
+    config_watcher: Option<ConfigWatcher>,
+
+    /// Applies the log level of a reloaded configuration
+    log_level_handler: Option<LogLevelHandler>,
+
+    /// Restarts of the indexing loop after transient errors
+    supervisor: Option<SupervisorSettings>,
+
+    /// Commands of the host application and the current state
+    control: IndexerControl,
+
+    /// Election of the only replica indexing the program
+    leader_election: Option<LeaderElection>,
+
+    /// Splitting of historical ranges between backfill workers
+    backfill: BackfillSettings,
+
+    /// Delivery of side effects queued by callbacks
+    outbox: OutboxSettings,
 }
 
+/// Callback applying a log level, e.g. through a reload handle of the tracing subscriber
+pub type LogLevelHandler = Box<dyn Fn(&str) + Send + Sync>;
+
 impl<E> Indexer<E>
 where
     E: ExecutorCallback + Send + Sync + 'static,
 {
     /// Creates new instance of indexer
     pub async fn build() -> IndexingResult<Self> {
-        let settings = get_configuration::<Configuration>()?;
-        let report = IndexerReport::default();
+        Self::build_with_overrides(&ConfigOverrides::default()).await
+    }
 
-        // Note: three different instances are used due to problems with async runtime in some k8s containers
-        let db_manager_1 = DbManager::connect(settings.db_settings.with_db())?;
-        let db_manager_2 = DbManager::connect(settings.db_settings.with_db())?;
-        let db_manager_3 = DbManager::connect(settings.db_settings.with_db())?;
+    /// Creates new instance of indexer, `overrides` take precedence over the configuration file
+    /// and environment variables
+    pub async fn build_with_overrides(overrides: &ConfigOverrides) -> IndexingResult<Self> {
+        let settings = Configuration::load(overrides)?;
+        settings.validate().into_result()?;
 
-        // Use INDEXER_MIGRATE env var first, then check for config (defaulting to false)
-        let migrate: bool = match std::env::var("INDEXER_MIGRATE") {
-            Ok(value) => matches!(value.as_str(), "1" | "true" | "TRUE" | "y"),
-            Err(_) => settings.indexer_settings.migrate.unwrap_or_default(),
-        };
+        if settings.indexer_settings.probe_on_start.unwrap_or_default() {
+            settings.probe().await.into_result()?;
+        }
+
+        let mut builder = IndexerBuilder::from_configuration(settings.clone());
+        if settings.indexer_settings.hot_reload.unwrap_or_default() {
+            builder = builder.config_watcher(ConfigWatcher::new(settings, overrides.clone()));
+        }
 
-        Ok(Self {
-            fetching_manager: FetchingManager::new(&settings, report.clone(), db_manager_1)?,
-            processing_manager: ProcessingManager::new(db_manager_2),
-            timestamp_interval: settings.indexer_settings.timestamp_interval,
-            db_manager: db_manager_3,
+        builder.build()
+    }
+
+    /// Assembles indexer from prepared components
+    pub(super) fn from_parts(
+        settings: &IndexerSettings,
+        fetching_manager: FetchingManager<E>,
+        processing_manager: ProcessingManager<E>,
+        db_manager: DbManager,
+        report: IndexerReport,
+        config_watcher: Option<ConfigWatcher>,
+    ) -> Self {
+        let commitment = settings.commitment.unwrap_or_default();
+        report.set_range(
+            settings.start.as_ref().and_then(IndexingBound::slot),
+            settings.end.as_ref().and_then(IndexingBound::slot),
+        );
+
+        let log_subscriber = settings.ws_connection_str.clone().map(|url| {
+            LogSubscriber::new(
+                url,
+                settings.program_id.clone(),
+                commitment.commitment_config(),
+            )
+        });
+
+        let leader_election = settings.leader_election.clone().map(|election| {
+            LeaderElection::new(db_manager.clone(), settings.program_id.clone(), election)
+        });
+
+        Self {
+            fetching_manager,
+            processing_manager,
+            timestamp_interval: settings.timestamp_interval,
+            db_manager,
             report,
-            migrate,
-        })
+            migrate: settings.migrate.unwrap_or_default(),
+            track_finalization: commitment.tracks_finalization(),
+            start: settings.start.clone(),
+            end: settings.end.clone(),
+            processing_order: settings.processing_order.unwrap_or_default(),
+            log_subscriber,
+            log_receiver: None,
+            config_watcher,
+            log_level_handler: None,
+            supervisor: settings.supervisor.clone(),
+            control: IndexerControl::default(),
+            leader_election,
+            backfill: settings.backfill.clone().unwrap_or_default(),
+            outbox: settings.outbox.clone().unwrap_or_default(),
+        }
     }
 
     #[cfg(test)]
@@ -71,7 +201,7 @@ where
         let report = IndexerReport::default();
         let fetching_manager =
             FetchingManager::new_mock(connection_string, report.clone(), db_manager.clone());
-        let processing_manager = ProcessingManager::new(db_manager.clone());
+        let processing_manager = ProcessingManager::new(db_manager.clone(), report.clone());
 
         Self {
             db_manager,
@@ -80,37 +210,497 @@ where
             timestamp_interval: UnixTimestamp::default(),
             report,
             migrate: false,
+            track_finalization: false,
+            start: None,
+            end: None,
+            processing_order: ProcessingOrder::default(),
+            log_subscriber: None,
+            log_receiver: None,
+            config_watcher: None,
+            log_level_handler: None,
+            supervisor: None,
+            control: IndexerControl::default(),
+            leader_election: None,
+            backfill: BackfillSettings::default(),
+            outbox: OutboxSettings::default(),
+        }
+    }
+
+    #[cfg(test)]
+    /// Replaces the logs subscription with a channel fed by the test,
+    /// notifications are tailed for `timestamp_interval` seconds between iterations
+    pub(crate) fn mock_log_subscription(
+        &mut self,
+        timestamp_interval: UnixTimestamp,
+    ) -> mpsc::Sender<TxSignature> {
+        let (sender, receiver) = mpsc::channel(LOG_NOTIFICATIONS_CAPACITY);
+        self.log_receiver = Some(receiver);
+        self.timestamp_interval = timestamp_interval;
+        sender
+    }
+
+    /// Returns a handle to pause, resume, stop or trigger the indexing and to observe its state
+    pub fn control(&self) -> IndexerControl {
+        self.control.clone()
+    }
+
+    /// Enables restarts of the indexing loop after transient errors
+    pub fn set_supervisor(&mut self, settings: Option<SupervisorSettings>) {
+        self.supervisor = settings;
+    }
+
+    /// Creates a dispatcher delivering side effects queued by callbacks into the outbox.
+    /// The dispatcher runs independently of the indexing, e.g. in a separate task.
+    pub fn outbox_dispatcher<D>(&self, delivery: D) -> OutboxDispatcher<D>
+    where
+        D: OutboxDelivery + Send,
+    {
+        OutboxDispatcher::new(self.db_manager.clone(), delivery, self.outbox.clone())
+    }
+
+    /// Creates a client delivering events to a webhook, its requests are logged in the indexer database
+    #[cfg(feature = "webhook")]
+    pub fn webhook_client(&self, settings: WebhookSettings) -> IndexingResult<WebhookClient> {
+        Ok(WebhookClient::new(self.db_manager.clone(), settings)?)
+    }
+
+    /// Returns a handle requesting a configuration reload, available if `hot_reload` is enabled
+    pub fn reload_handle(&self) -> Option<ReloadHandle> {
+        self.config_watcher.as_ref().map(ConfigWatcher::handle)
+    }
+
+    /// Returns settings of the fetching process, reloaded ones once they are applied
+    pub fn fetching_settings(&self) -> &FetchingSettings {
+        self.fetching_manager.fetching_settings()
+    }
+
+    /// Sets a callback applying `indexer_settings.log_level` of a reloaded configuration
+    pub fn set_log_level_handler(&mut self, handler: impl Fn(&str) + Send + Sync + 'static) {
+        self.log_level_handler = Some(Box::new(handler));
+    }
+
+    /// Updates the lag and the amount of pending work in the report,
+    /// failures leave the previous values in place
+    async fn update_progress(&self) {
+        match self.fetching_manager.get_slot().await {
+            Ok(slot) => self.report.set_tip_slot(slot),
+            Err(error) => warn!(error = %error, "Couldn't fetch the chain tip"),
         }
+
+        match self.db_manager.count_pending_txs().await {
+            Ok(pending) => self.report.set_pending_transactions(pending),
+            Err(error) => warn!(error = %error, "Couldn't count pending transactions"),
+        }
+    }
+
+    /// Applies changes of the configuration that do not require a restart
+    pub(crate) fn reload_settings(&mut self) {
+        let Some(settings) = self.config_watcher.as_mut().and_then(ConfigWatcher::poll) else {
+            return;
+        };
+
+        self.timestamp_interval = settings.indexer_settings.timestamp_interval;
+        self.fetching_manager
+            .set_fetching_settings(settings.fetcher_settings.unwrap_or_default());
+
+        if let (Some(handler), Some(log_level)) = (
+            &self.log_level_handler,
+            &settings.indexer_settings.log_level,
+        ) {
+            handler(log_level);
+        }
+    }
+
+    /// Runs database migrations
+    pub async fn run_migrations(&self) -> IndexingResult<()> {
+        self.db_manager.migrate().await?;
+
+        Ok(())
     }
 
-    /// Freezes the stream for the specified indexing interval
-    async fn wait(&self, timestamp: UnixTimestamp) {
+    /// Overrides the indexed range of history
+    pub fn set_range(&mut self, start: Option<IndexingBound>, end: Option<IndexingBound>) {
+        self.report.set_range(
+            start.as_ref().and_then(IndexingBound::slot),
+            end.as_ref().and_then(IndexingBound::slot),
+        );
+        self.start = start;
+        self.end = end;
+    }
+
+    /// Returns the scope of durable state of a bounded run,
+    /// the live indexing has an empty one
+    fn range_scope(&self) -> String {
+        match &self.end {
+            None => String::new(),
+            Some(end) => {
+                let start = self.start.as_ref().map(ToString::to_string);
+                format!("{}..{end}", start.unwrap_or_default())
+            }
+        }
+    }
+
+    /// Returns the name of the cursor scoped to the indexed range,
+    /// so a bounded run never moves the cursors of the live indexing
+    fn cursor_name(&self, name: &str) -> String {
+        match self.range_scope() {
+            scope if scope.is_empty() => name.to_string(),
+            scope => format!("{name}@{scope}"),
+        }
+    }
+
+    /// Returns durable state of the indexing process
+    pub async fn status(&self) -> IndexingResult<IndexerStatus> {
+        Ok(IndexerStatus {
+            most_recent_tx: self.db_manager.get_most_recent_tx().await?,
+            signatures_checkpoint: self
+                .db_manager
+                .get_cursor(SIGNATURES_CURSOR)
+                .await?
+                .and_then(|cursor| cursor.signature),
+            blocks_checkpoint: self
+                .db_manager
+                .get_cursor(BLOCKS_CURSOR)
+                .await?
+                .and_then(|cursor| cursor.slot),
+            pending_txs: self.db_manager.count_pending_txs().await?,
+            pending_signatures: self.db_manager.count_pending_signatures().await?,
+            backfill: self.db_manager.get_backfill_progress().await?,
+            last_error: self.db_manager.get_last_error().await?,
+        })
+    }
+
+    /// Fetches and processes again transactions that were left unprocessed
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn repair(&mut self) -> IndexingResult<usize> {
+        let pending = self.db_manager.get_pending_txs().await?;
+        info!(count = pending.len(), "Repairing unprocessed transactions");
+
+        for batch in pending.chunks(PENDING_BATCH_SIZE as usize) {
+            self.process_batch(&batch.to_vec()).await?;
+        }
+
+        Ok(pending.len())
+    }
+
+    /// Splits the slot range, both inclusive, into units shared by backfill workers,
+    /// returns the amount of new units
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn plan_backfill(&self, start_slot: Slot, end_slot: Slot) -> IndexingResult<u64> {
+        self.migrate_if_configured().await?;
+
+        let unit_size = self.backfill.unit_size;
+        let ranges = (start_slot..=end_slot)
+            .step_by(unit_size as usize)
+            .map(|start| (start, (start + unit_size - 1).min(end_slot)))
+            .collect::<Vec<_>>();
+
+        Ok(self.db_manager.insert_backfill_units(&ranges).await?)
+    }
+
+    /// Claims and indexes backfill units until none are left, returns the amount of indexed units.
+    ///
+    /// Units are walked block by block, so workers don't share any cursor. A unit of a failed
+    /// worker is returned to others, a unit of a crashed worker is claimed again after `claim_timeout`.
+    /// A unit claimed by another worker meanwhile is abandoned and the next one is claimed.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn run_backfill_worker(&mut self) -> IndexingResult<u64> {
+        self.migrate_if_configured().await?;
+
+        let worker = self.backfill.worker.clone().unwrap_or_else(default_holder);
+        let claim_timeout = self.backfill.claim_timeout as i64;
+        info!(worker, "Start backfill worker");
+
+        let mut completed = 0;
+        while let Some(unit) = self
+            .db_manager
+            .claim_backfill_unit(&worker, claim_timeout)
+            .await?
+        {
+            info!(
+                unit = unit.id,
+                start_slot = unit.start_slot,
+                end_slot = unit.end_slot,
+                "Backfill unit claimed"
+            );
+
+            if let Err(err) = self.index_backfill_unit(&unit, &worker).await {
+                if matches!(err.root(), IndexerError::ClaimLost) {
+                    warn!(unit = unit.id, "Backfill unit is claimed by another worker");
+                    continue;
+                }
+
+                let released = self
+                    .db_manager
+                    .update_backfill_unit(unit.id, &worker, BackfillStatus::Pending)
+                    .await;
+                if let Err(error) = released {
+                    warn!(error = %error, unit = unit.id, "Failed to release backfill unit");
+                }
+
+                if matches!(err.root(), IndexerError::Stopped) {
+                    break;
+                }
+                return Err(err);
+            }
+
+            if !self
+                .db_manager
+                .update_backfill_unit(unit.id, &worker, BackfillStatus::Completed)
+                .await?
+            {
+                warn!(unit = unit.id, "Backfill unit is claimed by another worker");
+                continue;
+            }
+            completed += 1;
+        }
+
+        info!(worker, completed, "Backfill worker is finished");
+        Ok(completed)
+    }
+
+    /// Indexes blocks of the unit, the claim is renewed after every range of blocks.
+    /// Fails with [IndexerError::ClaimLost] once the unit is claimed by another worker.
+    async fn index_backfill_unit(
+        &mut self,
+        unit: &BackfillUnit,
+        worker: &str,
+    ) -> IndexingResult<()> {
+        let mut start = unit.start_slot;
+        while start <= unit.end_slot {
+            self.checkpoint().await?;
+            let end = (start + BLOCK_RANGE_SIZE - 1).min(unit.end_slot);
+
+            for slot in self.fetching_manager.get_blocks(start, end).await? {
+                self.process_block(slot).await?;
+            }
+
+            if !self
+                .db_manager
+                .update_backfill_unit(unit.id, worker, BackfillStatus::Claimed)
+                .await?
+            {
+                return Err(IndexerError::ClaimLost);
+            }
+            start = end + 1;
+        }
+
+        Ok(())
+    }
+
+    /// Replays transactions stored within the slot range, both inclusive, through the executor.
+    ///
+    /// The RPC node is not called, transactions are restored from the raw transactions table
+    /// and their instructions are processed again.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn reindex(&mut self, start_slot: Slot, end_slot: Slot) -> IndexingResult<()> {
+        info!("Start reindexing");
+
+        let mut after = None;
+        loop {
+            let records = self
+                .db_manager
+                .get_raw_transactions(start_slot, end_slot, after.as_ref(), REINDEX_BATCH_SIZE)
+                .await?;
+
+            let Some(last) = records.last().cloned() else {
+                break;
+            };
+
+            let mut txs = vec![];
+            for record in &records {
+                if let Some(tx) = self.fetching_manager.replay_raw_tx(record).await? {
+                    txs.push(tx);
+                }
+            }
+
+            if !txs.is_empty() {
+                // Instructions processed before are marked as recorded and would be skipped
+                let hashes = txs.iter().map(|tx| tx.hash.clone()).collect::<Vec<_>>();
+                self.db_manager.delete_instructions(&hashes).await?;
+
+                self.processing_manager.process_batch(txs).await?;
+            }
+            after = Some(last);
+        }
+
+        info!("Reindexing is completed");
+        Ok(())
+    }
+
+    /// Freezes the stream for the specified indexing interval,
+    /// transactions notified by the logs subscription are processed meanwhile
+    async fn wait(&mut self, timestamp: UnixTimestamp) -> IndexingResult<()> {
         let interval = self.timestamp_interval - (Utc::now().timestamp() - timestamp);
+        let deadline = Instant::now() + Duration::from_secs(interval.max(0) as u64);
+
+        let Some(mut receiver) = self.log_receiver.take() else {
+            self.control.wait_until(deadline).await;
+            return Ok(());
+        };
+
+        let result = self.tail_notifications(&mut receiver, deadline).await;
+        self.log_receiver = Some(receiver);
+
+        result
+    }
+
+    /// Processes signatures received from the logs subscription until the deadline
+    async fn tail_notifications(
+        &mut self,
+        receiver: &mut Receiver<TxSignature>,
+        deadline: Instant,
+    ) -> IndexingResult<()> {
+        let control = self.control.clone();
+        loop {
+            tokio::select! {
+                _ = control.wait_until(deadline) => return Ok(()),
+                signature = receiver.recv() => {
+                    let Some(signature) = signature else {
+                        control.wait_until(deadline).await;
+                        return Ok(());
+                    };
 
-        if interval > 0 {
-            sleep(Duration::from_secs(interval as u64)).await;
+                    let mut batch = vec![signature];
+                    while let Ok(signature) = receiver.try_recv() {
+                        batch.push(signature);
+                    }
+
+                    self.process_batch(&batch).await?;
+                }
+            }
         }
     }
 
+    /// Starts the logs subscription if it is configured
+    fn subscribe_logs(&mut self) {
+        // Real-time notifications are useless for a range that ends in the past
+        if self.log_receiver.is_some() || self.end.is_some() {
+            return;
+        }
+
+        if let Some(log_subscriber) = self.log_subscriber.clone() {
+            let (sender, receiver) = mpsc::channel(LOG_NOTIFICATIONS_CAPACITY);
+            log_subscriber.spawn(sender);
+            self.log_receiver = Some(receiver);
+        }
+    }
+
+    /// Runs a single iteration of the configured strategy, `until` keeps the newest
+    /// signature between iterations of the signature-based ingestion
+    #[tracing::instrument(
+        name = "iteration",
+        level = "info",
+        skip(self, until),
+        fields(strategy = ?self.fetching_manager.strategy(), timestamp = %timestamp)
+    )]
+    async fn iteration(
+        &mut self,
+        until: &mut Option<String>,
+        timestamp: UnixTimestamp,
+    ) -> IndexingResult<()> {
+        match self.fetching_manager.strategy() {
+            FetchingStrategy::Signatures
+                if self.processing_order == ProcessingOrder::OldestFirst =>
+            {
+                self.ordered_iteration().await?
+            }
+            FetchingStrategy::Signatures => {
+                let newest = self.indexing_iteration(until, timestamp).await?;
+
+                // Transactions notified by the logs subscription are not used as a cursor,
+                // so the polling catches up on notifications that were missed
+                *until = match (newest, until.take()) {
+                    (Some(newest), _) => Some(newest),
+                    (None, Some(until)) => Some(until),
+                    (None, None) => self.db_manager.get_most_recent_tx().await?,
+                };
+            }
+            FetchingStrategy::Blocks => self.blocks_iteration().await?,
+        }
+
+        if self.track_finalization {
+            self.finalization_iteration().await?;
+        }
+
+        Ok(())
+    }
+
     /// Runs batch processing
     #[tracing::instrument(
-        level = "trace",
+        name = "batch",
+        level = "info",
         skip(batch, self),
         fields(
             first_signature = %batch.first().expect("Invalid batch").signature,
             last_signature = %batch.last().expect("Invalid batch").signature,
+            count = %batch.len(),
         ))]
     async fn process_batch(&mut self, batch: &TxBatch) -> IndexingResult<()> {
-        let txs = self.fetching_manager.fetch_batch(batch).await?;
+        self.checkpoint().await?;
+        self.control.set_batch(batch);
 
-        if !txs.is_empty() {
-            self.processing_manager.process_batch(txs).await?;
+        let result = async {
+            let txs = self.fetching_manager.fetch_batch(batch).await?;
+
+            if !txs.is_empty() {
+                self.processing_manager.process_batch(txs).await?;
+            }
+
+            Ok::<_, IndexerError>(())
         }
+        .await;
 
-        Ok(())
+        self.control.finish_batch();
+        result
     }
 
-    /// Runs indexer iteration for selected signature scope
+    /// Returns the signature of the start bound
+    fn start_signature(&self) -> Option<String> {
+        self.start
+            .as_ref()
+            .and_then(IndexingBound::signature)
+            .map(str::to_string)
+    }
+
+    /// Returns the signature of the end bound
+    fn end_signature(&self) -> Option<String> {
+        self.end
+            .as_ref()
+            .and_then(IndexingBound::signature)
+            .map(str::to_string)
+    }
+
+    /// Leaves signatures of the page that lie within the range
+    /// and tells whether the pagination crossed the start bound
+    fn page_within_range(&self, signatures: TxBatch) -> (TxBatch, bool) {
+        // Signatures are ordered from the newest to the oldest one
+        let crossed = self.start.as_ref().map_or(false, |start| {
+            signatures.last().map_or(false, |sign| start.is_older(sign))
+        });
+        let batch = signatures
+            .into_iter()
+            .filter(|sign| self.within_range(sign))
+            .collect();
+
+        (batch, crossed)
+    }
+
+    /// Whether the transaction lies between the start and the end bounds
+    fn within_range(&self, signature: &TxSignature) -> bool {
+        let after_start = self
+            .start
+            .as_ref()
+            .map_or(true, |start| !start.is_older(signature));
+        let before_end = self
+            .end
+            .as_ref()
+            .map_or(true, |end| !end.is_newer(signature));
+
+        after_start && before_end
+    }
+
+    /// Runs indexer iteration for selected signature scope and returns the newest signature of it
     #[tracing::instrument(
         level = "trace",
         skip(self, until),
@@ -123,17 +713,196 @@ where
         &mut self,
         until: &Option<String>,
         timestamp: UnixTimestamp,
-    ) -> IndexingResult<()> {
-        let mut before = None;
+    ) -> IndexingResult<Option<String>> {
+        let mut before = self.end_signature();
+        let until = until.clone().or_else(|| self.start_signature());
+
+        let mut newest = None;
         loop {
-            let signatures = self.fetching_manager.get_signatures(&before, until).await?;
+            let signatures = self
+                .fetching_manager
+                .get_signatures(&before, &until)
+                .await?;
 
             if signatures.is_empty() {
                 break;
             }
+            if newest.is_none() {
+                newest = signatures.first().map(|sign| sign.signature.clone());
+            }
             before = signatures.last().map(|sign| sign.signature.clone());
 
-            self.process_batch(&signatures).await?;
+            let (batch, crossed) = self.page_within_range(signatures);
+            if !batch.is_empty() {
+                self.process_batch(&batch).await?;
+            }
+            if crossed {
+                break;
+            }
+        }
+
+        Ok(newest)
+    }
+
+    /// Collects signatures newer than the durable cursor into the database
+    /// and returns the cursor of the newest one
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn collect_signatures(&mut self) -> IndexingResult<Option<Cursor>> {
+        let scope = self.range_scope();
+        let until = self
+            .db_manager
+            .get_cursor(&self.cursor_name(SIGNATURES_CURSOR))
+            .await?
+            .and_then(|cursor| cursor.signature)
+            .or_else(|| self.start_signature());
+        let mut before = self.end_signature();
+
+        // Leftovers of an interrupted collection are collected again
+        self.db_manager.clear_pending_signatures(&scope).await?;
+
+        let mut newest = None;
+        loop {
+            let signatures = self
+                .fetching_manager
+                .get_signatures(&before, &until)
+                .await?;
+
+            if signatures.is_empty() {
+                break;
+            }
+            before = signatures.last().map(|sign| sign.signature.clone());
+
+            // The cursor never moves past the end bound
+            let (batch, crossed) = self.page_within_range(signatures);
+            if newest.is_none() {
+                newest = batch.first().map(|sign| Cursor {
+                    slot: Some(sign.slot),
+                    signature: Some(sign.signature.clone()),
+                });
+            }
+            self.db_manager
+                .insert_pending_signatures(&scope, &batch)
+                .await?;
+            if crossed {
+                break;
+            }
+        }
+
+        if let Some(newest) = &newest {
+            self.db_manager
+                .set_cursor(&self.cursor_name(PENDING_CURSOR), newest)
+                .await?;
+        }
+
+        Ok(newest)
+    }
+
+    /// Runs indexer iteration processing signatures from the oldest to the newest one
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn ordered_iteration(&mut self) -> IndexingResult<()> {
+        let scope = self.range_scope();
+        let pending_cursor = self.cursor_name(PENDING_CURSOR);
+
+        // Signatures collected before a restart are processed without collecting them again
+        let newest = match self.db_manager.get_cursor(&pending_cursor).await? {
+            Some(cursor) => Some(cursor),
+            None => self.collect_signatures().await?,
+        };
+        let Some(newest) = newest else {
+            return Ok(());
+        };
+
+        loop {
+            let batch = self
+                .db_manager
+                .get_pending_signatures(&scope, PENDING_BATCH_SIZE)
+                .await?;
+
+            if batch.is_empty() {
+                break;
+            }
+
+            self.process_batch(&batch).await?;
+            self.db_manager
+                .delete_pending_signatures(&scope, &batch)
+                .await?;
+        }
+
+        self.db_manager
+            .set_cursor(&self.cursor_name(SIGNATURES_CURSOR), &newest)
+            .await?;
+        self.db_manager.delete_cursor(&pending_cursor).await?;
+
+        Ok(())
+    }
+
+    /// Runs batch processing of a single block
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn process_block(&mut self, slot: Slot) -> IndexingResult<()> {
+        let txs = self.fetching_manager.fetch_block(slot).await?;
+
+        if !txs.is_empty() {
+            self.processing_manager.process_batch(txs).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Runs block-based indexer iteration from the slot after the stored cursor up to the chain tip
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn blocks_iteration(&mut self) -> IndexingResult<()> {
+        let mut tip = self.fetching_manager.get_slot().await?;
+        if let Some(end) = self.end.as_ref().and_then(IndexingBound::slot) {
+            tip = tip.min(end);
+        }
+        let cursor_name = self.cursor_name(BLOCKS_CURSOR);
+        let cursor = self.db_manager.get_cursor(&cursor_name).await?;
+
+        // Without a stored cursor the ingestion starts at the start bound or the chain tip
+        let mut start = match cursor.and_then(|cursor| cursor.slot) {
+            Some(slot) => slot + 1,
+            None => self
+                .start
+                .as_ref()
+                .and_then(IndexingBound::slot)
+                .unwrap_or(tip),
+        };
+
+        while start <= tip {
+            let end = (start + BLOCK_RANGE_SIZE - 1).min(tip);
+
+            for slot in self.fetching_manager.get_blocks(start, end).await? {
+                self.process_block(slot).await?;
+            }
+
+            let cursor = Cursor {
+                slot: Some(end),
+                signature: None,
+            };
+            self.db_manager.set_cursor(&cursor_name, &cursor).await?;
+            start = end + 1;
+        }
+
+        Ok(())
+    }
+
+    /// Promotes confirmed transactions to finalized or flags them as dropped
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn finalization_iteration(&mut self) -> IndexingResult<()> {
+        let txs = self
+            .db_manager
+            .get_confirmed_txs(FINALIZATION_BATCH_SIZE)
+            .await?;
+
+        if txs.is_empty() {
+            return Ok(());
+        }
+
+        let updates = self.fetching_manager.get_commitment_updates(&txs).await?;
+        if !updates.is_empty() {
+            self.processing_manager
+                .process_commitment_updates(updates)
+                .await?;
         }
 
         Ok(())
@@ -144,19 +913,156 @@ where
     async fn run(&mut self) -> IndexingResult<()> {
         let mut until = None;
 
+        self.migrate_if_configured().await?;
+        self.acquire_leadership().await?;
+        self.subscribe_logs();
+
+        loop {
+            self.checkpoint().await?;
+            self.control.set_phase(IndexerPhase::Indexing);
+            let iteration_timestamp = Utc::now().timestamp();
+
+            self.iteration(&mut until, iteration_timestamp).await?;
+            self.publish_cursor(&until).await?;
+
+            if self.end.is_some() {
+                info!("Indexing range is completed");
+                return Ok(());
+            }
+
+            self.update_progress().await;
+            self.reload_settings();
+            self.control.set_phase(IndexerPhase::Waiting);
+            self.wait(iteration_timestamp).await?;
+        }
+    }
+
+    /// Runs database migrations if they are configured
+    async fn migrate_if_configured(&self) -> IndexingResult<()> {
         // If we have configured a migration, then it's failure is migrate error
         if self.migrate {
             self.db_manager.migrate().await?;
         }
 
+        Ok(())
+    }
+
+    /// Applies commands of the control handle and checks that the indexing lease is still held
+    async fn checkpoint(&self) -> IndexingResult<()> {
+        self.control.checkpoint().await?;
+
+        if self
+            .leader_election
+            .as_ref()
+            .map_or(false, LeaderElection::is_lost)
+        {
+            return Err(IndexerError::LeaseLost);
+        }
+        Ok(())
+    }
+
+    /// Waits as a standby replica until the indexing lease is acquired,
+    /// recording of instructions is fenced on the lease from then on
+    async fn acquire_leadership(&mut self) -> IndexingResult<()> {
+        let Some(election) = self.leader_election.as_mut() else {
+            self.report.set_role(IndexerRole::Leader);
+            return Ok(());
+        };
+
+        let mut waiting = false;
+        while !election.acquire().await? {
+            if !waiting {
+                info!(holder = election.holder(), "Waiting for the indexing lease");
+                waiting = true;
+            }
+            self.report.set_role(IndexerRole::Standby);
+            self.control.set_phase(IndexerPhase::Standby);
+
+            self.control
+                .wait_until(Instant::now() + election.renew_interval())
+                .await;
+            self.control.checkpoint().await?;
+        }
+
+        self.processing_manager.set_lease(Some(election.guard()));
+        self.report.set_role(IndexerRole::Leader);
+        Ok(())
+    }
+
+    /// Restarts the indexing loop after transient errors if a supervisor is configured
+    /// and switches to standby once the indexing lease is lost
+    async fn supervise(&mut self) -> IndexingResult<()> {
+        let mut supervisor = self.supervisor.clone().map(Supervisor::new);
+
         loop {
-            let iteration_timestamp = Utc::now().timestamp();
-            self.indexing_iteration(&until, iteration_timestamp).await?;
-            self.wait(iteration_timestamp).await;
+            self.report.set_available().await;
 
-            until = self.db_manager.get_most_recent_tx().await?;
+            let err = match self.run().await {
+                Ok(()) => return Ok(()),
+                Err(err) if matches!(err.root(), IndexerError::Stopped) => {
+                    err.get_trace();
+                    return Ok(());
+                }
+                Err(err) if matches!(err.root(), IndexerError::LeaseLost) => {
+                    warn!("Indexing lease is lost, switching to standby");
+                    self.report.set_role(IndexerRole::Standby);
+                    continue;
+                }
+                Err(err) => err,
+            };
+            err.get_trace();
+            self.report.set_unavailable().await;
+
+            if let Err(error) = self.db_manager.insert_error(&err.to_string()).await {
+                warn!(error = %error, "Failed to store indexing error");
+            }
+
+            let Some(delay) = supervisor
+                .as_mut()
+                .and_then(|supervisor| supervisor.restart_delay(&err))
+            else {
+                return Err(err);
+            };
+
+            self.report.inc_restarts();
+            warn!(
+                delay_ms = delay.as_millis() as u64,
+                restarts = self.report.get_restarts(),
+                "Restarting indexing after a transient error"
+            );
+            self.control.set_phase(IndexerPhase::Restarting);
+            self.control.wait_until(Instant::now() + delay).await;
+            if self.control.is_stopped() {
+                info!("Indexing is stopped");
+                return Ok(());
+            }
         }
     }
+
+    /// Shares the position the next iteration continues from through the control handle
+    async fn publish_cursor(&self, until: &Option<String>) -> IndexingResult<()> {
+        let cursor = match self.fetching_manager.strategy() {
+            FetchingStrategy::Signatures
+                if self.processing_order == ProcessingOrder::OldestFirst =>
+            {
+                self.db_manager
+                    .get_cursor(&self.cursor_name(SIGNATURES_CURSOR))
+                    .await?
+            }
+            FetchingStrategy::Signatures => until.clone().map(|signature| Cursor {
+                slot: None,
+                signature: Some(signature),
+            }),
+            FetchingStrategy::Blocks => {
+                self.db_manager
+                    .get_cursor(&self.cursor_name(BLOCKS_CURSOR))
+                    .await?
+            }
+        };
+        self.control.set_cursor(cursor);
+
+        Ok(())
+    }
 }
 
 pub trait IndexerEngine {
@@ -197,7 +1103,19 @@ where
             timestamp_interval,
             report,
             migrate,
-            ..
+            track_finalization,
+            start,
+            end,
+            processing_order,
+            log_subscriber,
+            log_receiver,
+            config_watcher,
+            log_level_handler,
+            supervisor,
+            control,
+            leader_election,
+            backfill,
+            outbox,
         } = self;
 
         Indexer {
@@ -207,22 +1125,35 @@ where
             timestamp_interval,
             report,
             migrate,
+            track_finalization,
+            start,
+            end,
+            processing_order,
+            log_subscriber,
+            log_receiver,
+            config_watcher,
+            log_level_handler,
+            supervisor,
+            control,
+            leader_election,
+            backfill,
+            outbox,
         }
     }
 
-    /// Indexes all instructions that were invoked during transactions in specified program
+    /// Indexes all instructions that were invoked during transactions in specified program.
+    /// With a supervisor configured, the indexing is restarted after transient errors.
     #[tracing::instrument(level = "debug", skip(self))]
     async fn start_indexing(&mut self) -> IndexingResult<()> {
         info!("Start indexing");
-        self.report.set_available().await;
+        let result = self.supervise().await;
 
-        if let Err(err) = self.run().await {
-            err.get_trace();
-            self.report.set_unavailable().await;
-
-            return Err(err);
+        if let Some(election) = self.leader_election.as_mut() {
+            election.release().await;
         }
-        Ok(())
+        self.control.set_phase(IndexerPhase::Stopped);
+
+        result
     }
 
     // Returns indexation report
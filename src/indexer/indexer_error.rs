@@ -1,14 +1,105 @@
 use {
-    crate::{fetcher::FetchingError, processor::ProcessingError, CallbackError},
+    crate::{
+        archive::ArchiveError,
+        configuration::ValidationReport,
+        fetcher::{FetchingError, NativeFetchingError},
+        processor::{NativeProcessingError, ProcessingError},
+        CallbackError,
+    },
     config::ConfigError,
+    solana_client::client_error::ClientErrorKind,
+    solana_sdk::clock::Slot,
     sqlx::migrate::MigrateError,
+    std::fmt,
     thiserror::Error,
-    tracing::error,
+    tracing::{error, info, warn},
 };
 
 /// Result of indexing process
 pub type IndexingResult<T> = std::result::Result<T, IndexerError>;
 
+/// How the indexing process should react to an error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCategory {
+    /// Temporary failure of RPC node or database, the operation can be retried
+    Transient,
+
+    /// Malformed or unexpected data, the transaction is skipped and dead-lettered
+    Data,
+
+    /// The indexing process can't continue and has to be stopped
+    Fatal,
+}
+
+impl fmt::Display for ErrorCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorCategory::Transient => write!(f, "transient"),
+            ErrorCategory::Data => write!(f, "data"),
+            ErrorCategory::Fatal => write!(f, "fatal"),
+        }
+    }
+}
+
+/// Allows callbacks to categorize their errors,
+/// e.g. `Err(CallbackError::from(error).context(ErrorCategory::Transient))`
+impl std::error::Error for ErrorCategory {}
+
+/// Location of an error in the indexed data
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// Signature of the transaction
+    pub signature: Option<String>,
+
+    /// Slot of the transaction block
+    pub slot: Option<Slot>,
+
+    /// Index of the instruction within the transaction
+    pub instruction: Option<u8>,
+}
+
+impl ErrorContext {
+    pub fn transaction(signature: impl Into<String>, slot: Option<Slot>) -> Self {
+        Self {
+            signature: Some(signature.into()),
+            slot,
+            instruction: None,
+        }
+    }
+
+    pub fn with_instruction(self, instruction: u8) -> Self {
+        Self {
+            instruction: Some(instruction),
+            ..self
+        }
+    }
+
+    /// Fills the missing fields from a wider context
+    fn merge(self, other: ErrorContext) -> Self {
+        Self {
+            signature: self.signature.or(other.signature),
+            slot: self.slot.or(other.slot),
+            instruction: self.instruction.or(other.instruction),
+        }
+    }
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut fields = vec![];
+        if let Some(signature) = &self.signature {
+            fields.push(format!("signature: {signature}"));
+        }
+        if let Some(slot) = self.slot {
+            fields.push(format!("slot: {slot}"));
+        }
+        if let Some(instruction) = self.instruction {
+            fields.push(format!("instruction: {instruction}"));
+        }
+        write!(f, "{}", fields.join(", "))
+    }
+}
+
 /// Collection of errors that are emitted during indexing process
 #[derive(Error, Debug)]
 pub enum IndexerError {
@@ -21,10 +112,85 @@ pub enum IndexerError {
     #[error(transparent)]
     ConfigErr(#[from] ConfigError),
     #[error(transparent)]
+    ValidationError(#[from] ValidationReport),
+    #[error(transparent)]
     CbError(#[from] CallbackError),
+    #[error(transparent)]
+    ArchiveError(#[from] ArchiveError),
+    #[error("Indexing is stopped")]
+    Stopped,
+    #[error("Indexing lease is lost")]
+    LeaseLost,
+    #[error("Backfill unit is claimed by another worker")]
+    ClaimLost,
+    #[error("{source} ({context})")]
+    InContext {
+        context: ErrorContext,
+        source: Box<IndexerError>,
+    },
 }
 
 impl IndexerError {
+    /// Attaches the location of the error, fields that are already known are kept
+    pub fn with_context(self, context: ErrorContext) -> Self {
+        match self {
+            IndexerError::InContext {
+                context: known,
+                source,
+            } => IndexerError::InContext {
+                context: known.merge(context),
+                source,
+            },
+            error => IndexerError::InContext {
+                context,
+                source: Box::new(error),
+            },
+        }
+    }
+
+    /// Returns the location of the error if it is known
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            IndexerError::InContext { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Returns the error without its context
+    pub fn root(&self) -> &IndexerError {
+        match self {
+            IndexerError::InContext { source, .. } => source.root(),
+            error => error,
+        }
+    }
+
+    /// Tells how the indexing process should react to the error
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            IndexerError::DbError(error) => db_error_category(error),
+            IndexerError::FetcherError(FetchingError::NativeFetcher(
+                NativeFetchingError::RpcCallLimit,
+            )) => ErrorCategory::Transient,
+            IndexerError::FetcherError(FetchingError::RpcClient(error)) => match error.kind() {
+                ClientErrorKind::SerdeJson(_)
+                | ClientErrorKind::SigningError(_)
+                | ClientErrorKind::TransactionError(_) => ErrorCategory::Data,
+                _ => ErrorCategory::Transient,
+            },
+            IndexerError::FetcherError(_) => ErrorCategory::Data,
+            IndexerError::ProcessorError(ProcessingError::NativeProcessor(
+                NativeProcessingError::EmptyCb,
+            )) => ErrorCategory::Fatal,
+            IndexerError::ProcessorError(_) => ErrorCategory::Data,
+            IndexerError::ConfigErr(_) | IndexerError::ValidationError(_) => ErrorCategory::Fatal,
+            IndexerError::CbError(error) => callback_error_category(error),
+            IndexerError::ArchiveError(ArchiveError::DbError(error)) => db_error_category(error),
+            IndexerError::ArchiveError(_) | IndexerError::Stopped => ErrorCategory::Fatal,
+            IndexerError::LeaseLost | IndexerError::ClaimLost => ErrorCategory::Transient,
+            IndexerError::InContext { source, .. } => source.category(),
+        }
+    }
+
     pub fn get_trace(&self) {
         match self {
             IndexerError::DbError(error) => error!(error = %error, "Database query failed"),
@@ -35,11 +201,70 @@ impl IndexerError {
             IndexerError::ConfigErr(error) => {
                 error!(error = %error, "Indexer configuration failed")
             }
+            IndexerError::ValidationError(report) => {
+                error!(error = %report, "Indexer configuration is invalid")
+            }
             IndexerError::CbError(error) => error!(error = %error, "Custom error occured"),
+            IndexerError::ArchiveError(error) => error!(error = %error, "Archive handling failed"),
+            IndexerError::Stopped => info!("Indexing is stopped"),
+            IndexerError::LeaseLost => error!("Indexing lease is lost"),
+            IndexerError::ClaimLost => warn!("Backfill unit is claimed by another worker"),
+            IndexerError::InContext { context, source } => {
+                error!(
+                    signature = context.signature,
+                    slot = context.slot,
+                    instruction = context.instruction,
+                    category = %source.category(),
+                    "Error occurred while indexing a transaction"
+                );
+                source.get_trace()
+            }
         }
     }
 }
 
+/// Tells how the indexing process should react to a database error
+pub fn db_error_category(error: &sqlx::Error) -> ErrorCategory {
+    match error {
+        sqlx::Error::Io(_)
+        | sqlx::Error::Tls(_)
+        | sqlx::Error::Protocol(_)
+        | sqlx::Error::PoolTimedOut
+        | sqlx::Error::PoolClosed
+        | sqlx::Error::WorkerCrashed => ErrorCategory::Transient,
+        sqlx::Error::Database(error) => match error.code().as_deref() {
+            // Connection exceptions, insufficient resources, operator intervention,
+            // serialization failures and deadlocks
+            Some(code)
+                if ["08", "53", "57"]
+                    .iter()
+                    .any(|class| code.starts_with(class))
+                    || code == "40001"
+                    || code == "40P01" =>
+            {
+                ErrorCategory::Transient
+            }
+            // Data exceptions and integrity constraint violations
+            Some(code) if code.starts_with("22") || code.starts_with("23") => ErrorCategory::Data,
+            _ => ErrorCategory::Fatal,
+        },
+        sqlx::Error::RowNotFound | sqlx::Error::ColumnDecode { .. } | sqlx::Error::Decode(_) => {
+            ErrorCategory::Data
+        }
+        _ => ErrorCategory::Fatal,
+    }
+}
+
+/// Tells how the indexing process should react to a callback error.
+/// Callbacks categorize errors with an [ErrorCategory] context, the rest are fatal.
+pub fn callback_error_category(error: &CallbackError) -> ErrorCategory {
+    error
+        .downcast_ref::<ErrorCategory>()
+        .or_else(|| error.chain().find_map(|cause| cause.downcast_ref()))
+        .copied()
+        .unwrap_or(ErrorCategory::Fatal)
+}
+
 impl From<MigrateError> for IndexerError {
     fn from(err: MigrateError) -> IndexerError {
         IndexerError::DbError(sqlx::Error::Migrate(Box::new(err)))
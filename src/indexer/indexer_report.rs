@@ -1,32 +1,114 @@
 use {
+    chrono::Utc,
     http::StatusCode,
     prometheus_client::{
         encoding::EncodeLabelSet,
-        metrics::{counter::Counter, family::Family},
+        metrics::{
+            counter::Counter,
+            family::Family,
+            gauge::Gauge,
+            histogram::{exponential_buckets, Histogram},
+        },
+        registry::Registry,
     },
     solana_client::{
         client_error::{ClientErrorKind, Result},
         rpc_request::RpcError,
     },
-    std::sync::Arc,
+    solana_sdk::clock::Slot,
+    std::{
+        sync::{atomic::AtomicU64, Arc, Mutex},
+        time::Duration,
+    },
     tokio::sync::RwLock,
 };
 
+use crate::{
+    fetcher::{RpcMethod, Tx},
+    indexer::IndexerRole,
+};
+
 /// Request counting metrics
 pub type RequestMetrics = Family<ResponseLabel, Counter>;
 
+/// Time in seconds that RPC calls spent waiting for the rate limiter
+pub type ThrottleMetrics = Family<MethodLabel, Counter<f64, AtomicU64>>;
+
 /// Indexer state for health reporting
 pub type IndexerState = Arc<RwLock<StatusCode>>;
 
+/// Time in seconds spent in executor hooks
+pub type CallbackMetrics = Family<HookLabel, Histogram, fn() -> Histogram>;
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
 pub struct ResponseLabel {
     code: String,
+    endpoint: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct MethodLabel {
+    method: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct HookLabel {
+    hook: String,
+}
+
+/// Slots of the indexed range and of the transactions processed within it
+#[derive(Debug, Default)]
+struct RangeSlots {
+    bounds: Option<(Slot, Slot)>,
+    processed: Option<(Slot, Slot)>,
+}
+
+/// Progress of the indexing process
+#[derive(Clone, Debug)]
+struct ProgressMetrics {
+    last_slot: Gauge,
+    last_blocktime: Gauge,
+    tip_slot: Gauge,
+    lag_slots: Gauge,
+    lag_seconds: Gauge,
+    transactions: Counter,
+    instructions: Counter,
+    pending_transactions: Gauge,
+    range_progress: Gauge<f64, AtomicU64>,
+    range_slots: Arc<Mutex<RangeSlots>>,
+    callback_duration: CallbackMetrics,
+    batch_duration: Histogram,
+}
+
+impl Default for ProgressMetrics {
+    fn default() -> Self {
+        Self {
+            last_slot: Gauge::default(),
+            last_blocktime: Gauge::default(),
+            tip_slot: Gauge::default(),
+            lag_slots: Gauge::default(),
+            lag_seconds: Gauge::default(),
+            transactions: Counter::default(),
+            instructions: Counter::default(),
+            pending_transactions: Gauge::default(),
+            range_progress: Gauge::default(),
+            range_slots: Arc::default(),
+            callback_duration: Family::new_with_constructor(|| {
+                Histogram::new(exponential_buckets(0.0005, 2.0, 16))
+            }),
+            batch_duration: Histogram::new(exponential_buckets(0.01, 2.0, 16)),
+        }
+    }
 }
 
 /// A structure holding the indexer monitoring data
 #[derive(Clone, Default)]
 pub struct IndexerReport {
     metrics: RequestMetrics,
+    throttle_metrics: ThrottleMetrics,
+    progress: ProgressMetrics,
+    restarts: Counter,
+    leader: Gauge,
     state: IndexerState,
 }
 
@@ -49,24 +131,228 @@ impl IndexerReport {
         self.metrics.clone()
     }
 
-    pub fn inc_metrics<T>(&self, result: &Result<T>) {
-        let response = if let Err(error) = result {
+    pub fn inc_metrics<T>(&self, endpoint: &str, result: &Result<T>) {
+        let code = if let Err(error) = result {
             if let ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) = &error.kind
             {
-                ResponseLabel {
-                    code: code.to_string(),
-                }
+                code.to_string()
             } else {
-                ResponseLabel {
-                    code: "500".to_string(),
-                }
+                "500".to_string()
             }
         } else {
-            ResponseLabel {
-                code: "200".to_string(),
-            }
+            "200".to_string()
+        };
+        let response = ResponseLabel {
+            code,
+            endpoint: endpoint.to_string(),
         };
 
         self.metrics.get_or_create(&response).inc();
     }
+
+    pub fn get_throttle_metrics(&self) -> ThrottleMetrics {
+        self.throttle_metrics.clone()
+    }
+
+    pub fn inc_throttle_wait(&self, method: RpcMethod, wait: Duration) {
+        let label = MethodLabel {
+            method: method.to_string(),
+        };
+
+        self.throttle_metrics
+            .get_or_create(&label)
+            .inc_by(wait.as_secs_f64());
+    }
+
+    /// Registers all metrics of the report in the registry
+    pub fn register(&self, registry: &mut Registry) {
+        registry.register(
+            "rpc_responses",
+            "RPC responses by code and endpoint",
+            self.metrics.clone(),
+        );
+        registry.register(
+            "rpc_throttle_seconds",
+            "Time RPC calls spent waiting for the rate limiter",
+            self.throttle_metrics.clone(),
+        );
+
+        let progress = &self.progress;
+        registry.register(
+            "last_indexed_slot",
+            "Slot of the newest indexed transaction",
+            progress.last_slot.clone(),
+        );
+        registry.register(
+            "last_indexed_blocktime",
+            "Block time of the newest indexed transaction",
+            progress.last_blocktime.clone(),
+        );
+        registry.register(
+            "chain_tip_slot",
+            "The most recent slot of the chain",
+            progress.tip_slot.clone(),
+        );
+        registry.register(
+            "lag_slots",
+            "Slots between the chain tip and the newest indexed transaction",
+            progress.lag_slots.clone(),
+        );
+        registry.register(
+            "lag_seconds",
+            "Time passed since the block of the newest indexed transaction",
+            progress.lag_seconds.clone(),
+        );
+        registry.register(
+            "transactions_processed",
+            "Transactions indexed",
+            progress.transactions.clone(),
+        );
+        registry.register(
+            "instructions_processed",
+            "Instructions passed to the executor",
+            progress.instructions.clone(),
+        );
+        registry.register(
+            "pending_transactions",
+            "Fetched transactions that are not processed yet",
+            progress.pending_transactions.clone(),
+        );
+        registry.register(
+            "range_progress",
+            "Share of the configured slot range that is indexed",
+            progress.range_progress.clone(),
+        );
+        registry.register(
+            "callback_duration_seconds",
+            "Time spent in executor hooks",
+            progress.callback_duration.clone(),
+        );
+        registry.register(
+            "batch_duration_seconds",
+            "Time spent processing a batch of transactions",
+            progress.batch_duration.clone(),
+        );
+        registry.register(
+            "indexer_restarts",
+            "Restarts of the indexing loop after transient errors",
+            self.restarts.clone(),
+        );
+        registry.register(
+            "leader",
+            "Whether the replica indexes the program, 0 for a standby replica",
+            self.leader.clone(),
+        );
+    }
+
+    /// Records the role of the replica
+    pub fn set_role(&self, role: IndexerRole) {
+        self.leader.set((role == IndexerRole::Leader) as i64);
+    }
+
+    /// Returns the role of the replica
+    pub fn get_role(&self) -> IndexerRole {
+        if self.leader.get() == 1 {
+            IndexerRole::Leader
+        } else {
+            IndexerRole::Standby
+        }
+    }
+
+    /// Records a restart of the indexing loop
+    pub fn inc_restarts(&self) {
+        self.restarts.inc();
+    }
+
+    /// Returns the amount of restarts of the indexing loop
+    pub fn get_restarts(&self) -> u64 {
+        self.restarts.get()
+    }
+
+    /// Records an indexed transaction
+    pub fn record_tx(&self, tx: &Tx) {
+        let progress = &self.progress;
+        progress.transactions.inc();
+
+        let slot = tx.slot as i64;
+        if slot > progress.last_slot.get() {
+            progress.last_slot.set(slot);
+        }
+        if tx.blocktime > progress.last_blocktime.get() {
+            progress.last_blocktime.set(tx.blocktime);
+        }
+
+        let mut range_slots = progress
+            .range_slots
+            .lock()
+            .expect("Range slots lock is poisoned");
+        let processed = match range_slots.processed {
+            Some((oldest, newest)) => (oldest.min(tx.slot), newest.max(tx.slot)),
+            None => (tx.slot, tx.slot),
+        };
+        range_slots.processed = Some(processed);
+
+        if let Some((start, end)) = range_slots.bounds {
+            let (oldest, newest) = (processed.0.max(start), processed.1.min(end));
+            let share = (newest.saturating_sub(oldest) + 1) as f64 / (end - start + 1) as f64;
+            progress.range_progress.set(share.min(1.0));
+        }
+    }
+
+    /// Records an instruction passed to the executor
+    pub fn inc_instructions(&self) {
+        self.progress.instructions.inc();
+    }
+
+    /// Records time spent in an executor hook
+    pub fn observe_callback(&self, hook: &str, duration: Duration) {
+        let label = HookLabel {
+            hook: hook.to_string(),
+        };
+
+        self.progress
+            .callback_duration
+            .get_or_create(&label)
+            .observe(duration.as_secs_f64());
+    }
+
+    /// Records time spent processing a batch of transactions
+    pub fn observe_batch(&self, duration: Duration) {
+        self.progress.batch_duration.observe(duration.as_secs_f64());
+    }
+
+    /// Records the most recent slot of the chain and updates the lag behind it
+    pub fn set_tip_slot(&self, slot: Slot) {
+        let progress = &self.progress;
+        progress.tip_slot.set(slot as i64);
+        progress
+            .lag_slots
+            .set((slot as i64 - progress.last_slot.get()).max(0));
+
+        let blocktime = progress.last_blocktime.get();
+        if blocktime > 0 {
+            progress
+                .lag_seconds
+                .set((Utc::now().timestamp() - blocktime).max(0));
+        }
+    }
+
+    /// Records the amount of transactions waiting for processing
+    pub fn set_pending_transactions(&self, count: i64) {
+        self.progress.pending_transactions.set(count);
+    }
+
+    /// Sets the slot range the progress is measured against, both bounds inclusive
+    pub fn set_range(&self, start: Option<Slot>, end: Option<Slot>) {
+        let mut range_slots = self
+            .progress
+            .range_slots
+            .lock()
+            .expect("Range slots lock is poisoned");
+
+        range_slots.bounds = match (start, end) {
+            (Some(start), Some(end)) if start <= end => Some((start, end)),
+            _ => None,
+        };
+    }
 }
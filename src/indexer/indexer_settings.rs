@@ -1,4 +1,12 @@
-use {serde::Deserialize, solana_sdk::clock::UnixTimestamp};
+use {
+    crate::{executor::TxSignature, fetcher::TxCommitment},
+    serde::Deserialize,
+    solana_sdk::{
+        clock::{Slot, UnixTimestamp},
+        commitment_config::CommitmentConfig,
+    },
+    std::fmt,
+};
 
 /// A structure for a general indexer configuration
 #[derive(Deserialize, Clone, Debug)]
@@ -9,6 +17,9 @@ pub struct IndexerSettings {
     /// An HTTP URL of working environment
     pub connection_str: String,
 
+    /// A WebSocket URL of working environment, enables real-time tailing through `logsSubscribe`
+    pub ws_connection_str: Option<String>,
+
     /// An interval between indexer calls
     pub timestamp_interval: UnixTimestamp,
 
@@ -17,4 +28,275 @@ pub struct IndexerSettings {
 
     /// Whether to run database migration on start
     pub migrate: Option<bool>,
+
+    /// Whether to check RPC and database connectivity on start (default - false)
+    pub probe_on_start: Option<bool>,
+
+    /// Whether to apply changes of the configuration file without a restart (default - false)
+    pub hot_reload: Option<bool>,
+
+    /// Log level passed to the handler set with `Indexer::set_log_level_handler`
+    pub log_level: Option<String>,
+
+    /// Additional RPC endpoints used for load sharing and failover
+    pub rpc_endpoints: Option<Vec<RpcEndpointSettings>>,
+
+    /// Commitment level of indexed transactions (default - confirmed)
+    pub commitment: Option<CommitmentMode>,
+
+    /// The oldest point of history to index, the whole history is indexed if absent
+    pub start: Option<IndexingBound>,
+
+    /// The newest point of history to index, indexing stops once it is reached
+    pub end: Option<IndexingBound>,
+
+    /// Order in which signatures of an iteration are processed (default - newest_first)
+    pub processing_order: Option<ProcessingOrder>,
+
+    /// Restarts of the indexing loop after transient errors, disabled if absent
+    pub supervisor: Option<SupervisorSettings>,
+
+    /// Election of the only replica indexing the program, every replica indexes if absent
+    pub leader_election: Option<LeaderElectionSettings>,
+
+    /// Splitting of historical ranges between backfill workers
+    pub backfill: Option<BackfillSettings>,
+
+    /// Delivery of side effects queued by callbacks into the outbox
+    pub outbox: Option<OutboxSettings>,
+}
+
+/// Settings of the outbox dispatcher
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct OutboxSettings {
+    /// Maximum amount of messages delivered in a single pass (default - 100)
+    pub batch_size: u64,
+
+    /// Interval in milliseconds between passes once the outbox is drained (default - 1000)
+    pub poll_interval: u64,
+}
+
+impl Default for OutboxSettings {
+    fn default() -> Self {
+        OutboxSettings {
+            batch_size: 100,
+            poll_interval: 1000,
+        }
+    }
+}
+
+/// Settings of the backfill work shared between workers
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct BackfillSettings {
+    /// Amount of slots in a single unit of work (default - 10000)
+    pub unit_size: Slot,
+
+    /// Time in seconds after which a unit of a silent worker is claimed again (default - 600)
+    pub claim_timeout: u64,
+
+    /// Identity of the worker (default - host name and process id)
+    pub worker: Option<String>,
+}
+
+impl Default for BackfillSettings {
+    fn default() -> Self {
+        BackfillSettings {
+            unit_size: 10_000,
+            claim_timeout: 600,
+            worker: None,
+        }
+    }
+}
+
+/// Settings of the leader election among replicas sharing a database
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct LeaderElectionSettings {
+    /// Time in seconds the lease is valid without renewal (default - 30)
+    pub lease_ttl: u64,
+
+    /// Interval in seconds between lease renewals of the leader
+    /// and takeover attempts of standby replicas (default - 10)
+    pub renew_interval: u64,
+
+    /// Identity of the replica (default - host name and process id)
+    pub holder: Option<String>,
+}
+
+impl Default for LeaderElectionSettings {
+    fn default() -> Self {
+        LeaderElectionSettings {
+            lease_ttl: 30,
+            renew_interval: 10,
+            holder: None,
+        }
+    }
+}
+
+/// Settings of restarting the indexing loop after transient errors
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct SupervisorSettings {
+    /// Delay before the first restart in milliseconds,
+    /// delays of consecutive restarts grow as Fibonacci numbers (default - 1000)
+    pub restart_delay: u64,
+
+    /// Maximum delay between restarts in milliseconds (default - 60000)
+    pub max_restart_delay: u64,
+
+    /// Maximum amount of restarts within `restart_window`,
+    /// the error is returned once it is exceeded (default - 10)
+    pub max_restarts: u64,
+
+    /// Period in seconds the restarts are counted over (default - 600)
+    pub restart_window: u64,
+}
+
+impl Default for SupervisorSettings {
+    fn default() -> Self {
+        SupervisorSettings {
+            restart_delay: 1000,
+            max_restart_delay: 60_000,
+            max_restarts: 10,
+            restart_window: 600,
+        }
+    }
+}
+
+/// Order in which signatures of an indexing iteration are processed
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessingOrder {
+    /// Each page of signatures is processed as soon as it is fetched
+    #[default]
+    NewestFirst,
+
+    /// Signatures of an iteration are collected into the database first
+    /// and processed in the order of their appearance on the chain
+    OldestFirst,
+}
+
+/// A point of program history limiting the indexed range
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexingBound {
+    /// Transaction signature, the transaction itself is excluded from the range
+    Signature(String),
+
+    /// Slot number, transactions of the slot are included in the range
+    Slot(Slot),
+
+    /// Unix timestamp, transactions of that second are included in the range
+    Timestamp(UnixTimestamp),
+}
+
+impl IndexingBound {
+    /// Returns the signature of the bound
+    pub fn signature(&self) -> Option<&str> {
+        match self {
+            IndexingBound::Signature(signature) => Some(signature),
+            _ => None,
+        }
+    }
+
+    /// Returns the slot of the bound
+    pub fn slot(&self) -> Option<Slot> {
+        match self {
+            IndexingBound::Slot(slot) => Some(*slot),
+            _ => None,
+        }
+    }
+
+    /// Whether the transaction is older than the bound.
+    /// Signature bounds are enforced by the RPC node, so they never match.
+    pub fn is_older(&self, signature: &TxSignature) -> bool {
+        match self {
+            IndexingBound::Signature(_) => false,
+            IndexingBound::Slot(slot) => signature.slot < *slot,
+            IndexingBound::Timestamp(timestamp) => signature
+                .block_time
+                .map_or(false, |block_time| block_time < *timestamp),
+        }
+    }
+
+    /// Whether the transaction is newer than the bound.
+    /// Signature bounds are enforced by the RPC node, so they never match.
+    pub fn is_newer(&self, signature: &TxSignature) -> bool {
+        match self {
+            IndexingBound::Signature(_) => false,
+            IndexingBound::Slot(slot) => signature.slot > *slot,
+            IndexingBound::Timestamp(timestamp) => signature
+                .block_time
+                .map_or(false, |block_time| block_time > *timestamp),
+        }
+    }
+}
+
+impl fmt::Display for IndexingBound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IndexingBound::Signature(signature) => write!(f, "signature:{signature}"),
+            IndexingBound::Slot(slot) => write!(f, "slot:{slot}"),
+            IndexingBound::Timestamp(timestamp) => write!(f, "timestamp:{timestamp}"),
+        }
+    }
+}
+
+/// Commitment level the indexer observes the chain at
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CommitmentMode {
+    /// Transactions are indexed as soon as they are confirmed
+    #[default]
+    Confirmed,
+
+    /// Transactions are indexed only after they are finalized
+    Finalized,
+
+    /// Transactions are indexed as soon as they are confirmed
+    /// and later promoted to finalized or flagged as dropped
+    ConfirmedWithFinalization,
+}
+
+impl CommitmentMode {
+    /// Returns commitment of RPC requests
+    pub fn commitment_config(&self) -> CommitmentConfig {
+        match self {
+            CommitmentMode::Finalized => CommitmentConfig::finalized(),
+            CommitmentMode::Confirmed | CommitmentMode::ConfirmedWithFinalization => {
+                CommitmentConfig::confirmed()
+            }
+        }
+    }
+
+    /// Returns commitment recorded for a freshly fetched transaction.
+    /// Transactions fetched at confirmed commitment stay confirmed unless finalization is tracked.
+    pub fn initial_tx_commitment(&self) -> TxCommitment {
+        match self {
+            CommitmentMode::Finalized => TxCommitment::Finalized,
+            CommitmentMode::Confirmed | CommitmentMode::ConfirmedWithFinalization => {
+                TxCommitment::Confirmed
+            }
+        }
+    }
+
+    /// Whether confirmed transactions are tracked until finalization
+    pub fn tracks_finalization(&self) -> bool {
+        *self == CommitmentMode::ConfirmedWithFinalization
+    }
+}
+
+/// A structure for an additional RPC endpoint configuration
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RpcEndpointSettings {
+    /// An HTTP URL of the endpoint
+    pub url: String,
+
+    /// Endpoints with lower value are preferred, `connection_str` has priority 0 (default - 1)
+    pub priority: Option<u32>,
+
+    /// Share of requests among endpoints with the same priority (default - 1)
+    pub weight: Option<u32>,
 }
@@ -0,0 +1,69 @@
+use {
+    crate::db::{BackfillProgress, ErrorRecord},
+    solana_sdk::clock::Slot,
+    std::fmt,
+};
+
+/// Durable state of the indexing process
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexerStatus {
+    /// Signature of the most recent indexed transaction
+    pub most_recent_tx: Option<String>,
+
+    /// Newest signature processed by the oldest-first processing
+    pub signatures_checkpoint: Option<String>,
+
+    /// Last slot processed by the block-based ingestion
+    pub blocks_checkpoint: Option<Slot>,
+
+    /// Amount of transactions that were fetched but not processed
+    pub pending_txs: i64,
+
+    /// Amount of collected signatures waiting for processing
+    pub pending_signatures: i64,
+
+    /// Amount of backfill units by their state
+    pub backfill: BackfillProgress,
+
+    /// The latest error that stopped the indexing process
+    pub last_error: Option<ErrorRecord>,
+}
+
+impl fmt::Display for IndexerStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let none = || "-".to_string();
+
+        writeln!(
+            f,
+            "most recent transaction: {}",
+            self.most_recent_tx.clone().unwrap_or_else(none)
+        )?;
+        writeln!(
+            f,
+            "signatures checkpoint:   {}",
+            self.signatures_checkpoint.clone().unwrap_or_else(none)
+        )?;
+        writeln!(
+            f,
+            "blocks checkpoint:       {}",
+            self.blocks_checkpoint
+                .map(|slot| slot.to_string())
+                .unwrap_or_else(none)
+        )?;
+        writeln!(f, "pending transactions:    {}", self.pending_txs)?;
+        writeln!(f, "pending signatures:      {}", self.pending_signatures)?;
+        writeln!(
+            f,
+            "backfill units:          {} pending, {} claimed, {} completed",
+            self.backfill.pending, self.backfill.claimed, self.backfill.completed
+        )?;
+        write!(
+            f,
+            "last error:              {}",
+            self.last_error
+                .as_ref()
+                .map(|error| format!("{} (at {})", error.message, error.occurred_at))
+                .unwrap_or_else(none)
+        )
+    }
+}
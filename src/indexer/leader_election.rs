@@ -0,0 +1,220 @@
+use {
+    crate::{
+        db::{DbManager, IndexerDbRecording},
+        indexer::indexer_settings::LeaderElectionSettings,
+    },
+    std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        time::Duration,
+    },
+    tokio::{
+        task::JoinHandle,
+        time::{interval, Instant, MissedTickBehavior},
+    },
+    tracing::{info, warn},
+};
+
+/// Role of the replica in the leader election
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexerRole {
+    /// The replica holds the lease and indexes the program
+    Leader,
+
+    /// The replica waits until the lease expires
+    Standby,
+}
+
+/// Handle of the lease for the writers, lets them stop as soon as the replica stops leading.
+///
+/// The lease is checked before an instruction callback runs and fenced when the instruction
+/// is recorded, so processing is at-least-once: a callback that was running while the lease
+/// was taken over isn't recorded, and the new leader runs it again.
+#[derive(Debug, Clone)]
+pub struct LeaseGuard {
+    /// Name of the lease
+    name: String,
+
+    /// Identity of the replica
+    holder: String,
+
+    /// Whether the lease was taken over or couldn't be renewed in time
+    lost: Arc<AtomicBool>,
+}
+
+impl LeaseGuard {
+    pub fn new(name: String, holder: String) -> Self {
+        Self {
+            name,
+            holder,
+            lost: Arc::default(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn holder(&self) -> &str {
+        &self.holder
+    }
+
+    /// Whether the held lease was lost
+    pub fn is_lost(&self) -> bool {
+        self.lost.load(Ordering::Relaxed)
+    }
+}
+
+/// Makes only one of the replicas sharing a database index the program.
+/// The replica holding a lease in the database is the leader, the lease is renewed
+/// in the background and taken over by a standby replica once it expires.
+pub struct LeaderElection {
+    db_manager: DbManager,
+
+    /// Name of the lease, replicas of the same program compete for it
+    name: String,
+
+    /// Identity of the replica
+    holder: String,
+
+    settings: LeaderElectionSettings,
+
+    /// Whether the lease was taken over or couldn't be renewed in time
+    lost: Arc<AtomicBool>,
+
+    /// Background renewal of the held lease
+    renewal: Option<JoinHandle<()>>,
+}
+
+impl LeaderElection {
+    pub fn new(db_manager: DbManager, name: String, settings: LeaderElectionSettings) -> Self {
+        let holder = settings.holder.clone().unwrap_or_else(default_holder);
+
+        Self {
+            db_manager,
+            name,
+            holder,
+            settings,
+            lost: Arc::default(),
+            renewal: None,
+        }
+    }
+
+    /// Identity of the replica
+    pub fn holder(&self) -> &str {
+        &self.holder
+    }
+
+    /// Returns the handle of the lease that follows its renewals
+    pub fn guard(&self) -> LeaseGuard {
+        LeaseGuard {
+            name: self.name.clone(),
+            holder: self.holder.clone(),
+            lost: self.lost.clone(),
+        }
+    }
+
+    /// Interval between lease renewals and takeover attempts
+    pub fn renew_interval(&self) -> Duration {
+        Duration::from_secs(self.settings.renew_interval)
+    }
+
+    /// Tries to acquire the lease and starts renewing it, returns whether the replica is the leader
+    pub async fn acquire(&mut self) -> Result<bool, sqlx::Error> {
+        if self.renewal.is_some() && !self.is_lost() {
+            return Ok(true);
+        }
+        self.stop_renewal();
+
+        let acquired = self
+            .db_manager
+            .try_acquire_lease(&self.name, &self.holder, self.settings.lease_ttl as i64)
+            .await?;
+
+        if acquired {
+            info!(lease = self.name, holder = self.holder, "Lease acquired");
+            self.lost.store(false, Ordering::Relaxed);
+            self.renewal = Some(self.spawn_renewal());
+        }
+        Ok(acquired)
+    }
+
+    /// Whether the held lease was lost
+    pub fn is_lost(&self) -> bool {
+        self.lost.load(Ordering::Relaxed)
+    }
+
+    /// Stops renewing the lease and gives it up, so a standby replica takes over immediately
+    pub async fn release(&mut self) {
+        if self.renewal.is_none() {
+            return;
+        }
+        self.stop_renewal();
+
+        match self
+            .db_manager
+            .release_lease(&self.name, &self.holder)
+            .await
+        {
+            Ok(()) => info!(lease = self.name, holder = self.holder, "Lease released"),
+            Err(error) => warn!(error = %error, "Failed to release the lease"),
+        }
+    }
+
+    fn stop_renewal(&mut self) {
+        if let Some(renewal) = self.renewal.take() {
+            renewal.abort();
+        }
+    }
+
+    fn spawn_renewal(&self) -> JoinHandle<()> {
+        let db_manager = self.db_manager.clone();
+        let (name, holder) = (self.name.clone(), self.holder.clone());
+        let ttl = self.settings.lease_ttl;
+        let lost = self.lost.clone();
+        let mut ticks = interval(self.renew_interval());
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        tokio::spawn(async move {
+            let mut renewed_at = Instant::now();
+            // The first tick completes immediately
+            ticks.tick().await;
+
+            loop {
+                ticks.tick().await;
+                match db_manager
+                    .try_acquire_lease(&name, &holder, ttl as i64)
+                    .await
+                {
+                    Ok(true) => renewed_at = Instant::now(),
+                    Ok(false) => {
+                        warn!(lease = name, "Lease is taken over by another replica");
+                        break;
+                    }
+                    Err(error) if renewed_at.elapsed() < Duration::from_secs(ttl) => {
+                        warn!(error = %error, lease = name, "Failed to renew the lease");
+                    }
+                    Err(error) => {
+                        warn!(error = %error, lease = name, "Lease expired before renewal");
+                        break;
+                    }
+                }
+            }
+            lost.store(true, Ordering::Relaxed);
+        })
+    }
+}
+
+impl Drop for LeaderElection {
+    fn drop(&mut self) {
+        self.stop_renewal();
+    }
+}
+
+/// Identity of the replica made of the host name and process id
+pub(crate) fn default_holder() -> String {
+    let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "indexer".to_string());
+    format!("{host}-{}", std::process::id())
+}
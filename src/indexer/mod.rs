@@ -1,10 +1,22 @@
+pub mod config_watcher;
+pub mod indexer_builder;
+pub mod indexer_control;
 pub mod indexer_engine;
 pub mod indexer_error;
 pub mod indexer_report;
 pub mod indexer_settings;
+pub mod indexer_status;
+pub mod leader_election;
+pub mod supervisor;
 
 #[cfg(test)]
 mod tests;
 
+pub use config_watcher::*;
+pub use indexer_builder::*;
+pub use indexer_control::*;
 pub use indexer_error::*;
 pub use indexer_report::*;
+pub use indexer_status::*;
+pub use leader_election::*;
+pub use supervisor::*;
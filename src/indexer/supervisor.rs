@@ -0,0 +1,55 @@
+use {
+    crate::{
+        indexer::indexer_settings::SupervisorSettings, utils::fibonacci, ErrorCategory,
+        IndexerError,
+    },
+    std::{
+        collections::VecDeque,
+        time::{Duration, Instant},
+    },
+};
+
+/// Decides whether the indexing loop is restarted after an error
+pub struct Supervisor {
+    settings: SupervisorSettings,
+
+    /// Times of the restarts within the restart window
+    restarts: VecDeque<Instant>,
+}
+
+impl Supervisor {
+    pub fn new(settings: SupervisorSettings) -> Self {
+        Self {
+            settings,
+            restarts: VecDeque::new(),
+        }
+    }
+
+    /// Returns the delay before the next restart,
+    /// or nothing if the error isn't transient or restarts are too frequent
+    pub fn restart_delay(&mut self, error: &IndexerError) -> Option<Duration> {
+        if error.category() != ErrorCategory::Transient {
+            return None;
+        }
+
+        let now = Instant::now();
+        let window = Duration::from_secs(self.settings.restart_window);
+        while self
+            .restarts
+            .front()
+            .map_or(false, |restart| now.duration_since(*restart) > window)
+        {
+            self.restarts.pop_front();
+        }
+
+        if self.restarts.len() as u64 >= self.settings.max_restarts {
+            return None;
+        }
+
+        let delay = (self.settings.restart_delay * fibonacci(self.restarts.len() as u64))
+            .min(self.settings.max_restart_delay);
+        self.restarts.push_back(now);
+
+        Some(Duration::from_millis(delay))
+    }
+}
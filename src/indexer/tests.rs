@@ -1,14 +1,22 @@
 use {
     super::*,
     crate::{
-        db::{test_connection_manager::ConnectionManager, DbManager},
+        configuration::{ConfigOverrides, Configuration},
+        db::{test_connection_manager::ConnectionManager, DbManager, IndexerDbRecording},
+        executor::TxSignature,
         fetcher::{FetchingError, NativeFetchingError},
-        processor::CallbackResult,
-        ExecutorCallback, ExecutorControlFlow, Indexer, IndexerEngine, Instruction,
+        processor::{CallbackResult, NativeProcessingError, ProcessingError},
+        CommitmentMode, ExecutorCallback, ExecutorControlFlow, Indexer, IndexerEngine,
+        IndexerSettings, IndexingBound, Instruction, LeaderElectionSettings, ProcessingOrder,
+        SupervisorSettings,
     },
     anyhow::anyhow,
     enum_extract::let_extract,
     http::StatusCode,
+    solana_client::{
+        mock_sender::Mocks, nonblocking::rpc_client::RpcClient, rpc_request::RpcRequest,
+    },
+    solana_sdk::{pubkey::Pubkey, signature::Signature},
     sqlx::postgres::PgConnectOptions,
 };
 
@@ -34,6 +42,29 @@ impl ExecutorCallback for EmptyProcessor {
     }
 }
 
+/// Counts the transactions passed to the executor
+#[derive(Clone, Default)]
+struct CountingProcessor {
+    txs: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+}
+
+impl ExecutorCallback for CountingProcessor {
+    async fn process_instruction(
+        &mut self,
+        _instruction: &Instruction,
+    ) -> CallbackResult<ExecutorControlFlow> {
+        Ok(ExecutorControlFlow::Pass)
+    }
+
+    async fn process_parsed_transaction(&mut self, _tx: &Tx) -> TxResult {
+        self.txs.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        Ok(ControlFlowWithData {
+            control_flow: ExecutorControlFlow::Pass,
+            data: None,
+        })
+    }
+}
+
 async fn get_indexer(url: String, options: PgConnectOptions) -> Indexer<TestProcessor> {
     let db_manager = DbManager::connect(options).expect("Failed to create manager");
 
@@ -105,3 +136,486 @@ async fn check_fail_state_test() {
     assert!(res.is_err());
     assert_eq!(StatusCode::SERVICE_UNAVAILABLE, *state.read().await);
 }
+
+fn tx_signature(slot: u64, block_time: Option<i64>) -> TxSignature {
+    TxSignature {
+        signature: "test_signature".to_string(),
+        slot,
+        err: None,
+        memo: None,
+        block_time,
+        confirmation_status: None,
+    }
+}
+
+#[test]
+fn commitment_mode_test() {
+    assert_eq!(
+        CommitmentMode::Confirmed.initial_tx_commitment(),
+        TxCommitment::Confirmed
+    );
+    assert_eq!(
+        CommitmentMode::Finalized.initial_tx_commitment(),
+        TxCommitment::Finalized
+    );
+    assert_eq!(
+        CommitmentMode::ConfirmedWithFinalization.initial_tx_commitment(),
+        TxCommitment::Confirmed
+    );
+    assert!(!CommitmentMode::Confirmed.tracks_finalization());
+    assert!(CommitmentMode::ConfirmedWithFinalization.tracks_finalization());
+}
+
+#[test]
+fn indexing_bound_test() {
+    let slot = IndexingBound::Slot(100);
+    assert!(slot.is_older(&tx_signature(99, None)));
+    assert!(!slot.is_older(&tx_signature(100, None)));
+    assert!(slot.is_newer(&tx_signature(101, None)));
+    assert!(!slot.is_newer(&tx_signature(100, None)));
+
+    let timestamp = IndexingBound::Timestamp(1_000);
+    assert!(timestamp.is_older(&tx_signature(0, Some(999))));
+    assert!(timestamp.is_newer(&tx_signature(0, Some(1_001))));
+    assert!(!timestamp.is_older(&tx_signature(0, None)));
+    assert!(!timestamp.is_newer(&tx_signature(0, None)));
+
+    let signature = IndexingBound::Signature("test_signature".to_string());
+    assert_eq!(signature.signature(), Some("test_signature"));
+    assert!(!signature.is_older(&tx_signature(0, Some(0))));
+    assert!(!signature.is_newer(&tx_signature(u64::MAX, Some(i64::MAX))));
+}
+
+fn get_indexer_settings() -> IndexerSettings {
+    IndexerSettings {
+        program_id: solana_sdk::pubkey::Pubkey::default().to_string(),
+        connection_str: "http://localhost:8899".into(),
+        ws_connection_str: None,
+        timestamp_interval: 0,
+        rpc_timeout: None,
+        migrate: None,
+        probe_on_start: None,
+        hot_reload: None,
+        log_level: None,
+        rpc_endpoints: None,
+        commitment: None,
+        start: None,
+        end: None,
+        processing_order: None,
+        supervisor: None,
+        leader_election: None,
+        backfill: None,
+        outbox: None,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[should_panic(expected = "Instruction processed")]
+async fn builder_test() {
+    let connection_manager = ConnectionManager::build().await;
+    let pool = sqlx::PgPool::connect_lazy_with(connection_manager.get_connection_options());
+
+    let mut indexer = IndexerBuilder::new(get_indexer_settings())
+        .pool(pool)
+        .rpc_client(RpcClient::new_mock("succeeds".into()))
+        .executor(TestProcessor)
+        .build()
+        .unwrap();
+
+    indexer.start_indexing().await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn end_bound_cursor_test() {
+    let connection_manager = ConnectionManager::build().await;
+    let pool = sqlx::PgPool::connect_lazy_with(connection_manager.get_connection_options());
+    let db_manager = DbManager::connect(connection_manager.get_connection_options())
+        .expect("Failed to create manager");
+
+    // The first page starts past the end bound, the next ones are older than the start bound
+    let (past_end, within_range) = (Signature::new_unique(), Signature::new_unique());
+    let page = [(past_end, 300), (within_range, 200)].map(|(signature, slot)| TxSignature {
+        signature: signature.to_string(),
+        slot,
+        err: None,
+        memo: None,
+        block_time: None,
+        confirmation_status: None,
+    });
+    let mocks = Mocks::from([(
+        RpcRequest::GetSignaturesForAddress,
+        serde_json::to_value(page).unwrap(),
+    )]);
+
+    let mut indexer = IndexerBuilder::new(IndexerSettings {
+        start: Some(IndexingBound::Slot(150)),
+        end: Some(IndexingBound::Slot(250)),
+        processing_order: Some(ProcessingOrder::OldestFirst),
+        ..get_indexer_settings()
+    })
+    .pool(pool)
+    .rpc_client(RpcClient::new_mock_with_mocks("succeeds".into(), mocks))
+    .executor(EmptyProcessor)
+    .build()
+    .unwrap();
+
+    indexer.start_indexing().await.unwrap();
+
+    // The cursor of the bounded run is kept apart from the one of the live indexing
+    let cursor = db_manager
+        .get_cursor("signatures@slot:150..slot:250")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(cursor.signature, Some(within_range.to_string()));
+    assert_eq!(cursor.slot, Some(200));
+    assert!(db_manager.get_cursor("signatures").await.unwrap().is_none());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn builder_without_db_test() {
+    let result = IndexerBuilder::<TestProcessor>::new(get_indexer_settings()).build();
+
+    let_extract!(
+        Err(IndexerError::ConfigErr(config::ConfigError::NotFound(key))),
+        result,
+        panic!("Unexpected result")
+    );
+    assert_eq!(key, "db_settings");
+}
+
+#[test]
+fn progress_metrics_test() {
+    let report = IndexerReport::default();
+    let mut registry = prometheus_client::registry::Registry::default();
+    report.register(&mut registry);
+
+    report.set_range(Some(100), Some(199));
+    for slot in [150, 199] {
+        report.record_tx(&crate::Tx::new(
+            slot.to_string(),
+            slot,
+            1_700_000_000,
+            vec![],
+            vec![],
+        ));
+    }
+    report.inc_instructions();
+    report.set_tip_slot(250);
+    report.set_pending_transactions(3);
+
+    let mut metrics = String::new();
+    prometheus_client::encoding::text::encode(&mut metrics, &registry).unwrap();
+
+    for line in [
+        "last_indexed_slot 199",
+        "last_indexed_blocktime 1700000000",
+        "chain_tip_slot 250",
+        "lag_slots 51",
+        "transactions_processed_total 2",
+        "instructions_processed_total 1",
+        "pending_transactions 3",
+        "range_progress 0.5",
+        "batch_duration_seconds_count 0",
+    ] {
+        assert!(metrics.contains(line), "{line} is missing in:\n{metrics}");
+    }
+}
+
+#[test]
+fn error_category_test() {
+    let fetching_error = IndexerError::from(FetchingError::from(NativeFetchingError::RpcCallLimit));
+    assert_eq!(fetching_error.category(), ErrorCategory::Transient);
+
+    let data_error = IndexerError::from(FetchingError::from(NativeFetchingError::WrongEncoding));
+    assert_eq!(data_error.category(), ErrorCategory::Data);
+
+    let processing_error =
+        IndexerError::from(ProcessingError::from(NativeProcessingError::EmptyCb));
+    assert_eq!(processing_error.category(), ErrorCategory::Fatal);
+
+    let callback_error = IndexerError::CbError(anyhow!("Unknown account"));
+    assert_eq!(callback_error.category(), ErrorCategory::Fatal);
+
+    let callback_error =
+        IndexerError::CbError(anyhow!("Unknown account").context(ErrorCategory::Data));
+    assert_eq!(callback_error.category(), ErrorCategory::Data);
+
+    let error = data_error
+        .with_context(ErrorContext::transaction("tx", Some(123)).with_instruction(1))
+        .with_context(ErrorContext::transaction("batch", Some(100)));
+    assert_eq!(
+        error.context(),
+        Some(&ErrorContext {
+            signature: Some("tx".into()),
+            slot: Some(123),
+            instruction: Some(1),
+        })
+    );
+    assert_eq!(error.category(), ErrorCategory::Data);
+    assert!(matches!(
+        error.root(),
+        IndexerError::FetcherError(FetchingError::NativeFetcher(
+            NativeFetchingError::WrongEncoding
+        ))
+    ));
+    assert_eq!(
+        error.to_string(),
+        "Wrong type of transaction encoding (signature: tx, slot: 123, instruction: 1)"
+    );
+}
+
+#[test]
+fn supervisor_test() {
+    let mut supervisor = Supervisor::new(SupervisorSettings {
+        restart_delay: 100,
+        max_restart_delay: 250,
+        max_restarts: 5,
+        restart_window: 600,
+    });
+    let transient = IndexerError::from(FetchingError::from(NativeFetchingError::RpcCallLimit));
+    let fatal = IndexerError::CbError(anyhow!("Unknown account"));
+
+    assert_eq!(supervisor.restart_delay(&fatal), None);
+
+    let delays = (0..6)
+        .map(|_| supervisor.restart_delay(&transient))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        delays,
+        [100, 100, 100, 200, 250]
+            .map(|delay| Some(std::time::Duration::from_millis(delay)))
+            .into_iter()
+            .chain([None])
+            .collect::<Vec<_>>()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn control_test() {
+    let control = IndexerControl::default();
+    control.checkpoint().await.unwrap();
+
+    control.pause();
+    let paused = tokio::spawn({
+        let control = control.clone();
+        async move { control.checkpoint().await }
+    });
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert_eq!(control.snapshot().phase, IndexerPhase::Paused);
+    assert!(control.snapshot().paused);
+    control.resume();
+    paused.await.unwrap().unwrap();
+
+    control.trigger();
+    let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(60);
+    tokio::time::timeout(
+        std::time::Duration::from_secs(1),
+        control.wait_until(deadline),
+    )
+    .await
+    .expect("Iteration trigger is ignored");
+
+    control.stop();
+    assert!(control.snapshot().stop_requested);
+    assert!(matches!(
+        control.checkpoint().await,
+        Err(IndexerError::Stopped)
+    ));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn stop_indexing_test() {
+    let connection_manager = ConnectionManager::build().await;
+    let mut indexer = get_indexer(
+        "succeeds".into(),
+        connection_manager.get_connection_options(),
+    )
+    .await;
+
+    let control = indexer.control();
+    control.stop();
+    indexer.start_indexing().await.unwrap();
+
+    assert_eq!(control.snapshot().phase, IndexerPhase::Stopped);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn leader_election_test() {
+    let connection_manager = ConnectionManager::build().await;
+    let db_manager = DbManager::connect(connection_manager.get_connection_options())
+        .expect("Failed to create manager");
+
+    let replica = |holder: &str| {
+        LeaderElection::new(
+            db_manager.clone(),
+            "program".into(),
+            LeaderElectionSettings {
+                holder: Some(holder.into()),
+                ..Default::default()
+            },
+        )
+    };
+    let mut leader = replica("leader");
+    let mut standby = replica("standby");
+
+    assert!(leader.acquire().await.unwrap());
+    assert!(!standby.acquire().await.unwrap());
+    assert!(leader.acquire().await.unwrap());
+
+    leader.release().await;
+    assert!(standby.acquire().await.unwrap());
+    assert!(!leader.acquire().await.unwrap());
+    assert!(!standby.is_lost());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn plan_backfill_test() {
+    let connection_manager = ConnectionManager::build().await;
+    let indexer = get_indexer(
+        "succeeds".into(),
+        connection_manager.get_connection_options(),
+    )
+    .await;
+
+    assert_eq!(indexer.plan_backfill(0, 25_000).await.unwrap(), 3);
+    assert_eq!(indexer.plan_backfill(0, 25_000).await.unwrap(), 0);
+
+    let status = indexer.status().await.unwrap();
+    assert_eq!(status.backfill.pending, 3);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn log_notifications_dedup_test() {
+    let connection_manager = ConnectionManager::build().await;
+    let db_manager = DbManager::connect(connection_manager.get_connection_options())
+        .expect("Failed to create manager");
+    let processor = CountingProcessor::default();
+
+    let mut indexer = Indexer::new_mock("succeeds".into(), db_manager);
+    indexer.set_executor(processor.clone());
+    let sender = indexer.mock_log_subscription(1);
+
+    // The mock node notifies and polls the same transaction
+    let signature = RpcClient::new_mock("succeeds".to_string())
+        .get_signatures_for_address(&Pubkey::default())
+        .await
+        .unwrap()
+        .remove(0);
+    sender.send(signature.clone()).await.unwrap();
+    sender.send(signature).await.unwrap();
+
+    let control = indexer.control();
+    tokio::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_millis(2500)).await;
+        control.stop();
+    });
+    indexer.start_indexing().await.unwrap();
+
+    assert_eq!(
+        sender.capacity(),
+        sender.max_capacity(),
+        "Notifications are not consumed"
+    );
+    assert_eq!(
+        processor.txs.load(std::sync::atomic::Ordering::Relaxed),
+        1,
+        "Notified transaction is processed again by the polling"
+    );
+}
+
+/// Writes the configuration file with the modification time of `second`
+fn write_config(path: &std::path::Path, content: &str, second: u64) {
+    std::fs::write(path, content).expect("Failed to write configuration");
+    std::fs::File::options()
+        .write(true)
+        .open(path)
+        .and_then(|file| {
+            file.set_modified(
+                std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(second),
+            )
+        })
+        .expect("Failed to set modification time");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn config_watcher_test() {
+    let connection_manager = ConnectionManager::build().await;
+    let pool = sqlx::PgPool::connect_lazy_with(connection_manager.get_connection_options());
+
+    let original = std::fs::read_to_string("configuration.yaml").expect("Failed to read file");
+    let reloaded = |fetcher_settings: &str| {
+        format!(
+            "{}fetcher_settings:\n{fetcher_settings}",
+            original.replace(
+                "  timestamp_interval: 5\n",
+                "  timestamp_interval: 5\n  log_level: \"debug\"\n"
+            )
+        )
+    };
+    let fetcher_settings = |retry_limit: u64, batch_size: usize| {
+        format!(
+            "  rpc_request_timeout: 100\n  retry_limit: {retry_limit}\n  transaction_batch_size: {batch_size}\n"
+        )
+    };
+
+    let path = std::env::temp_dir().join(format!("{}.yaml", uuid::Uuid::new_v4()));
+    write_config(&path, &original, 1_000);
+    let configuration =
+        Configuration::load_from(path.to_str().unwrap(), &ConfigOverrides::default())
+            .expect("Failed to load configuration");
+
+    let log_levels = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+    let mut indexer = IndexerBuilder::from_configuration(configuration.clone())
+        .pool(pool)
+        .rpc_client(RpcClient::new_mock("succeeds".into()))
+        .executor(EmptyProcessor)
+        .config_watcher(ConfigWatcher::with_path(
+            &path,
+            configuration,
+            ConfigOverrides::default(),
+        ))
+        .build()
+        .unwrap();
+    indexer.set_log_level_handler({
+        let log_levels = log_levels.clone();
+        move |level| log_levels.lock().unwrap().push(level.to_string())
+    });
+
+    indexer.reload_settings();
+    assert_eq!(indexer.fetching_settings().retry_limit, 10);
+
+    // A modified file is applied
+    write_config(&path, &reloaded(&fetcher_settings(3, 50)), 1_001);
+    indexer.reload_settings();
+    assert_eq!(indexer.fetching_settings().retry_limit, 3);
+    assert_eq!(indexer.fetching_settings().transaction_batch_size, 50);
+    assert_eq!(*log_levels.lock().unwrap(), vec!["debug".to_string()]);
+
+    // Invalid settings are rejected
+    write_config(&path, &reloaded(&fetcher_settings(4, 0)), 1_002);
+    indexer.reload_settings();
+    assert_eq!(indexer.fetching_settings().retry_limit, 3);
+
+    // Changes that require a restart are rejected
+    let structural = reloaded(&fetcher_settings(5, 50)).replace(
+        "https://api.devnet.solana.com",
+        "https://api.mainnet-beta.solana.com",
+    );
+    write_config(&path, &structural, 1_003);
+    indexer.reload_settings();
+    assert_eq!(indexer.fetching_settings().retry_limit, 3);
+
+    // An unchanged modification time is reloaded only on request
+    write_config(&path, &reloaded(&fetcher_settings(6, 50)), 1_003);
+    indexer.reload_settings();
+    assert_eq!(indexer.fetching_settings().retry_limit, 3);
+    indexer
+        .reload_handle()
+        .expect("Hot reload is disabled")
+        .reload();
+    indexer.reload_settings();
+    assert_eq!(indexer.fetching_settings().retry_limit, 6);
+
+    std::fs::remove_file(&path).expect("Failed to remove configuration");
+}
@@ -42,35 +42,93 @@
 //!   This variable overrides `indexer_settings.migrate` setting in the configuration file.
 //!   Default is false.
 //!
+//! - **INDEXER__SECTION__FIELD**
+//!
+//!   Overrides a single field of the configuration file, e.g. `INDEXER__DB_SETTINGS__PASSWORD`
+//!   sets `db_settings.password`.
+//!
 //! # Configuration
 //!
 //! Indexer is configured via a configuration file specified by `INDEXER_CFG` environment variable.
+//! Every field of the file can be overridden by an `INDEXER__SECTION__FIELD` environment variable,
+//! and both can be overridden in code with [ConfigOverrides] passed to [Indexer::build_with_overrides].
 //! Please refer to [Configuration] schema for details on available settings.
 
+mod archive;
+#[cfg(feature = "cli")]
+pub mod cli;
 mod configuration;
 mod db;
 mod executor;
 mod fetcher;
 mod indexer;
 mod processor;
+mod sink;
+#[cfg(feature = "otel")]
+pub mod telemetry;
 mod utils;
 
 pub use {
-    configuration::{get_configuration, Configuration},
+    archive::{
+        ArchiveError, ArchiveFormat, ArchiveRange, ArchiveResult, ArchiveSummary, Exporter,
+        Importer, InstructionRecord, TransactionRecord,
+    },
+    configuration::{
+        get_configuration, get_configuration_with_overrides, ConfigOverrides, Configuration,
+        ValidationIssue, ValidationReport,
+    },
+    db::{
+        BackfillProgress, BackfillStatus, BackfillUnit, Cursor, DatabaseSettings, DeadLetterRecord,
+        ErrorRecord, OutboxRecord, WebhookDeliveryRecord,
+    },
     executor::{
         CbResult, ControlFlowWithData, Executor, ExecutorCallback, ExecutorControlFlow, TxMeta,
         TxResult, TxSignature,
     },
-    fetcher::{fetching_settings::FetchingSettings, Tx, TxBatch},
+    fetcher::{
+        fetching_settings::{
+            FetchingSettings, FetchingStrategy, MethodRateLimit, RateLimitSettings,
+        },
+        RpcMethod, Tx, TxBatch, TxCommitment,
+    },
     indexer::{
-        indexer_engine::{Indexer, IndexerEngine},
-        indexer_error::{IndexerError, IndexingResult},
-        indexer_report::{IndexerReport, IndexerState, RequestMetrics},
-        indexer_settings::IndexerSettings,
+        config_watcher::{ConfigWatcher, ReloadHandle},
+        indexer_builder::IndexerBuilder,
+        indexer_control::{BatchProgress, IndexerControl, IndexerPhase, IndexerSnapshot},
+        indexer_engine::{Indexer, IndexerEngine, LogLevelHandler},
+        indexer_error::{ErrorCategory, ErrorContext, IndexerError, IndexingResult},
+        indexer_report::{
+            CallbackMetrics, IndexerReport, IndexerState, RequestMetrics, ThrottleMetrics,
+        },
+        indexer_settings::{
+            BackfillSettings, CommitmentMode, IndexerSettings, IndexingBound,
+            LeaderElectionSettings, OutboxSettings, ProcessingOrder, RpcEndpointSettings,
+            SupervisorSettings,
+        },
+        indexer_status::IndexerStatus,
+        leader_election::{IndexerRole, LeaseGuard},
     },
     processor::{
         instruction::Instruction,
+        outbox::{Outbox, OutboxDelivery, OutboxDispatcher, OutboxMessage},
         processor_error::{CallbackError, CallbackResult},
     },
+    sink::sink_event::SinkEvent,
+    sink::stream::{Acknowledgement, IndexedStream, StreamEvent, StreamItem, StreamingExecutor},
     solana_transaction_status::EncodedConfirmedTransactionWithStatusMeta,
 };
+
+#[cfg(feature = "kafka")]
+pub use sink::mq::KafkaBroker;
+#[cfg(feature = "nats")]
+pub use sink::mq::NatsBroker;
+#[cfg(feature = "mq")]
+pub use sink::{
+    mq::{BrokerMessage, InMemoryBroker, MessageBroker, MqExecutor},
+    sink_settings::{MessageKey, MqSettings},
+};
+#[cfg(feature = "webhook")]
+pub use sink::{
+    sink_settings::WebhookSettings,
+    webhook::{WebhookClient, WebhookExecutor, SIGNATURE_HEADER, WEBHOOK_TOPIC},
+};
@@ -3,7 +3,7 @@ use {
 };
 
 /// Struct representing an Instruction entity from a Solana transaction
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Instruction {
     /// Sequence index in transaction
     pub id: u8,
@@ -42,4 +42,10 @@ impl Instruction {
             data,
         }
     }
+
+    /// A key identifying the instruction across indexing attempts, made of the transaction hash
+    /// and the instruction id, the instruction is recorded under it once processed
+    pub fn idempotency_key(&self) -> String {
+        format!("{}:{}", self.tx_hash, self.id)
+    }
 }
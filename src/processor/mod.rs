@@ -1,4 +1,5 @@
 pub mod instruction;
+pub mod outbox;
 pub mod processing_manager;
 pub mod processor_error;
 
@@ -6,5 +7,6 @@ pub mod processor_error;
 mod tests;
 
 pub use instruction::*;
+pub use outbox::*;
 pub use processing_manager::*;
 pub use processor_error::*;
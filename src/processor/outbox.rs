@@ -0,0 +1,197 @@
+use {
+    super::Instruction,
+    crate::{
+        db::{DbManager, IndexerDbRecording, OutboxRecord},
+        indexer::{callback_error_category, indexer_settings::OutboxSettings, IndexingResult},
+        CallbackResult, ErrorCategory,
+    },
+    futures::Future,
+    std::time::Duration,
+    tokio::time::sleep,
+    tracing::{debug, warn},
+};
+
+/// A side effect queued by a callback, delivered once the instruction is recorded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutboxMessage {
+    /// Destination of the message, e.g. a URL or a queue name
+    pub topic: String,
+
+    /// Serialized message
+    pub payload: String,
+}
+
+/// Side effects of a single instruction.
+/// The messages are stored in the same database transaction as the instruction marker,
+/// so they are neither lost nor duplicated if the indexer crashes in between.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outbox {
+    key: String,
+    messages: Vec<OutboxMessage>,
+}
+
+impl Outbox {
+    pub fn new(instruction: &Instruction) -> Self {
+        Self {
+            key: instruction.idempotency_key(),
+            messages: vec![],
+        }
+    }
+
+    /// Idempotency key of the instruction the messages belong to
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Queues a message
+    pub fn push(&mut self, topic: impl Into<String>, payload: impl Into<String>) {
+        self.messages.push(OutboxMessage {
+            topic: topic.into(),
+            payload: payload.into(),
+        });
+    }
+
+    /// Returns the queued messages
+    pub fn messages(&self) -> &[OutboxMessage] {
+        &self.messages
+    }
+
+    /// Idempotency keys of the queued messages, made of the instruction key and the message index
+    pub(crate) fn message_keys(&self) -> impl Iterator<Item = (String, &OutboxMessage)> {
+        self.messages
+            .iter()
+            .enumerate()
+            .map(|(index, message)| (format!("{}:{index}", self.key), message))
+    }
+}
+
+/// Delivers queued side effects to external systems.
+/// A message may be delivered again if the dispatcher stops before recording the delivery,
+/// receivers should deduplicate messages by their idempotency key.
+pub trait OutboxDelivery {
+    fn deliver(
+        &mut self,
+        message: &OutboxRecord,
+    ) -> impl Future<Output = CallbackResult<()>> + Send;
+
+    /// Topic of the messages the delivery handles (default - every topic)
+    fn topic(&self) -> Option<&str> {
+        None
+    }
+
+    /// Maximum amount of messages passed to [OutboxDelivery::deliver_batch] at once (default - 1)
+    fn max_batch(&self) -> usize {
+        1
+    }
+
+    /// Delivers consecutive messages of the same topic, all of them are retried if it fails.
+    /// The messages are delivered one by one by default.
+    fn deliver_batch(
+        &mut self,
+        messages: &[OutboxRecord],
+    ) -> impl Future<Output = CallbackResult<()>> + Send
+    where
+        Self: Send,
+    {
+        async move {
+            for message in messages {
+                self.deliver(message).await?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Delivers messages of the outbox in the order they were queued
+pub struct OutboxDispatcher<D> {
+    db_manager: DbManager,
+    delivery: D,
+    settings: OutboxSettings,
+}
+
+impl<D> OutboxDispatcher<D>
+where
+    D: OutboxDelivery + Send,
+{
+    pub fn new(db_manager: DbManager, delivery: D, settings: OutboxSettings) -> Self {
+        Self {
+            db_manager,
+            delivery,
+            settings,
+        }
+    }
+
+    /// Returns the delivery of the dispatcher
+    pub fn delivery(&self) -> &D {
+        &self.delivery
+    }
+
+    /// Delivers a batch of undelivered messages, returns the amount of delivered messages.
+    /// The pass stops at the first failed delivery, so the order of messages is kept.
+    /// Messages rejected with a data error are dead-lettered and skipped.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn dispatch_pending(&mut self) -> IndexingResult<usize> {
+        let messages = self
+            .db_manager
+            .get_undelivered_messages(self.delivery.topic(), self.settings.batch_size as i64)
+            .await?;
+
+        let max_batch = self.delivery.max_batch().max(1);
+        let batches = messages
+            .chunk_by(|first, second| first.topic == second.topic)
+            .flat_map(|topic| topic.chunks(max_batch));
+
+        let mut delivered = 0;
+        for batch in batches {
+            match self.delivery.deliver_batch(batch).await {
+                Ok(()) => (),
+                Err(error) if callback_error_category(&error) == ErrorCategory::Data => {
+                    warn!(
+                        error = %error,
+                        key = batch[0].idempotency_key,
+                        count = batch.len(),
+                        "Outbox messages are rejected and dead-lettered",
+                    );
+                    for message in batch {
+                        self.db_manager
+                            .dead_letter_message(message.id, &error.to_string())
+                            .await?;
+                    }
+                    continue;
+                }
+                Err(error) => {
+                    warn!(
+                        error = %error,
+                        key = batch[0].idempotency_key,
+                        count = batch.len(),
+                        attempts = batch[0].attempts + 1,
+                        "Failed to deliver outbox messages",
+                    );
+                    for message in batch {
+                        self.db_manager
+                            .record_delivery_failure(message.id, &error.to_string())
+                            .await?;
+                    }
+                    break;
+                }
+            }
+
+            for message in batch {
+                self.db_manager.mark_delivered(message.id).await?;
+                debug!(key = message.idempotency_key, "Outbox message delivered");
+            }
+            delivered += batch.len();
+        }
+
+        Ok(delivered)
+    }
+
+    /// Delivers messages until an error occurs, waits for `poll_interval` once the outbox is drained
+    pub async fn run(&mut self) -> IndexingResult<()> {
+        loop {
+            if self.dispatch_pending().await? < self.settings.batch_size as usize {
+                sleep(Duration::from_millis(self.settings.poll_interval)).await;
+            }
+        }
+    }
+}
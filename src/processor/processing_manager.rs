@@ -1,12 +1,14 @@
 use {
-    super::{Instruction, NativeProcessingError, ProcessingResult},
+    super::{Instruction, NativeProcessingError, Outbox, ProcessingError, ProcessingResult},
     crate::{
         db::{DbManager, IndexerDbRecording},
-        fetcher::{IndexingStatus, Tx},
-        Executor, ExecutorCallback,
+        fetcher::{IndexingStatus, Tx, TxCommitment},
+        ErrorCategory, ErrorContext, Executor, ExecutorCallback, ExecutorControlFlow, IndexerError,
+        IndexerReport, IndexingResult, LeaseGuard,
     },
     solana_program::pubkey,
-    tracing::{debug, info},
+    std::time::Instant,
+    tracing::{debug, info, info_span, Instrument},
 };
 
 /// A manager that handles calling the callback function for fetched instructions and storing indexing state
@@ -16,19 +18,32 @@ pub struct ProcessingManager<E> {
 
     /// Responsible for database interaction
     db_manager: DbManager,
+
+    /// Indexer status report
+    report: IndexerReport,
+
+    /// Lease that has to be held while instructions are recorded
+    lease: Option<LeaseGuard>,
 }
 
 impl<E> ProcessingManager<E>
 where
     E: ExecutorCallback + Send + Sync + 'static,
 {
-    pub fn new(db_manager: DbManager) -> Self {
+    pub fn new(db_manager: DbManager, report: IndexerReport) -> Self {
         ProcessingManager {
             executor: Executor::None,
             db_manager,
+            report,
+            lease: None,
         }
     }
 
+    /// Fences recording of instructions on the lease of the leader election
+    pub fn set_lease(&mut self, lease: Option<LeaseGuard>) {
+        self.lease = lease;
+    }
+
     /// Sets a Processor entity for further processing
     pub fn set_executor(&mut self, executor: Executor<E>) {
         self.executor = executor;
@@ -41,40 +56,116 @@ where
         ProcessingManager {
             executor,
             db_manager: self.db_manager,
+            report: self.report,
+            lease: self.lease,
         }
     }
 
-    #[tracing::instrument(level = "info", skip(self))]
-    pub async fn process_tx(&mut self, tx: &Tx) -> ProcessingResult<()> {
-        for instruction in self.get_instructions(tx)? {
-            debug!(
-                tx_hash = instruction.tx_hash,
-                id = instruction.id,
-                "Processing instruction",
-            );
-            if !self.db_manager.recorded_instruction(&instruction).await? {
-                if let Executor::Executor(executor) = &self.executor {
-                    let control_flow = executor
-                        .lock()
-                        .await
-                        .process_instruction(&instruction)
-                        .await?;
-                    match control_flow {
-                        crate::ExecutorControlFlow::Skip => continue,
-                        crate::ExecutorControlFlow::Pass => (),
-                        crate::ExecutorControlFlow::Stop => break,
-                    }
-                } else {
-                    return Err(NativeProcessingError::EmptyCb.into());
-                }
+    pub async fn process_tx(&mut self, tx: &Tx) -> IndexingResult<()> {
+        self.process_instructions(tx, &mut vec![]).await
+    }
+
+    /// Calls the executor for instructions of the transaction,
+    /// keys of the instructions recorded meanwhile are appended to `recorded`
+    #[tracing::instrument(
+        name = "transaction",
+        level = "info",
+        skip(self, tx, recorded),
+        fields(signature = %tx.hash, slot = tx.slot)
+    )]
+    async fn process_instructions(
+        &self,
+        tx: &Tx,
+        recorded: &mut Vec<String>,
+    ) -> IndexingResult<()> {
+        let context = ErrorContext::transaction(&tx.hash, Some(tx.slot));
+        let instructions = self
+            .get_instructions(tx)
+            .map_err(|error| IndexerError::from(error).with_context(context.clone()))?;
+
+        for instruction in instructions {
+            let control_flow =
+                self.process_instruction(tx, &instruction)
+                    .await
+                    .map_err(|error| {
+                        error.with_context(context.clone().with_instruction(instruction.id))
+                    })?;
 
-                self.db_manager.insert_instruction(&instruction).await?;
-                debug!("Instruction processed");
+            match control_flow {
+                ExecutorControlFlow::Pass => recorded.push(instruction.idempotency_key()),
+                ExecutorControlFlow::Skip => (),
+                ExecutorControlFlow::Stop => break,
             }
         }
         Ok(())
     }
 
+    /// Calls the executor for an instruction that wasn't recorded yet and records it
+    async fn process_instruction(
+        &self,
+        tx: &Tx,
+        instruction: &Instruction,
+    ) -> IndexingResult<ExecutorControlFlow> {
+        debug!(
+            tx_hash = instruction.tx_hash,
+            id = instruction.id,
+            "Processing instruction",
+        );
+        if self.db_manager.recorded_instruction(instruction).await? {
+            return Ok(ExecutorControlFlow::Skip);
+        }
+        // A replica that lost the lease doesn't run side effects of the callback,
+        // the lease is checked again when the instruction is recorded
+        if let Some(lease) = &self.lease {
+            if lease.is_lost()
+                || !self
+                    .db_manager
+                    .holds_lease(lease.name(), lease.holder())
+                    .await?
+            {
+                return Err(IndexerError::LeaseLost);
+            }
+        }
+
+        let Executor::Executor(executor) = &self.executor else {
+            return Err(ProcessingError::from(NativeProcessingError::EmptyCb).into());
+        };
+
+        let started_at = Instant::now();
+        let span = info_span!(
+            "instruction_callback",
+            signature = %instruction.tx_hash,
+            slot = tx.slot,
+            program_id = %instruction.program_id,
+            instruction_id = instruction.id,
+        );
+        let mut outbox = Outbox::new(instruction);
+        let control_flow = async {
+            executor
+                .lock()
+                .await
+                .process_instruction_with_outbox(instruction, &mut outbox)
+                .await
+        }
+        .instrument(span)
+        .await?;
+        self.report
+            .observe_callback("process_instruction", started_at.elapsed());
+
+        if control_flow == ExecutorControlFlow::Pass {
+            let held = self
+                .db_manager
+                .insert_instruction_with_outbox(instruction, &outbox, self.lease.as_ref())
+                .await?;
+            if !held {
+                return Err(IndexerError::LeaseLost);
+            }
+            self.report.inc_instructions();
+            debug!("Instruction processed");
+        }
+        Ok(control_flow)
+    }
+
     #[tracing::instrument(
         level = "info",
         skip(self, txs),
@@ -84,14 +175,85 @@ where
             count = %txs.len()
         )
     )]
-    /// Imposes a callback on the instructions of transaction in job
-    pub async fn process_batch(&mut self, txs: Vec<Tx>) -> ProcessingResult<()> {
+    /// Imposes a callback on the instructions of transaction in job.
+    /// Transactions that fail with a data error are dead-lettered and left pending.
+    /// Transactions are marked indexed once the executor completes the batch, otherwise
+    /// instructions recorded by the batch are forgotten with their outbox messages
+    /// and processed again.
+    pub async fn process_batch(&mut self, txs: Vec<Tx>) -> IndexingResult<()> {
+        let started_at = Instant::now();
+
+        let mut recorded = vec![];
+        let mut indexed = vec![];
+        let mut result = Ok(());
         for mut tx in txs {
-            self.process_tx(&tx).await?;
-            tx.indexing_status = IndexingStatus::Indexed;
+            match self.process_instructions(&tx, &mut recorded).await {
+                Ok(()) => {
+                    tx.indexing_status = IndexingStatus::Indexed;
+                    indexed.push(tx);
+                }
+                Err(error) if error.category() == ErrorCategory::Data => {
+                    self.db_manager.dead_letter(&error).await?
+                }
+                Err(error) => {
+                    result = Err(error);
+                    break;
+                }
+            }
+        }
+
+        if let Err(error) = self.complete_batch().await {
+            self.db_manager
+                .delete_recorded_instructions(&recorded)
+                .await?;
+            return Err(error);
+        }
+        result?;
+
+        for tx in indexed {
             self.db_manager.update_transaction(&tx).await?;
+            self.report.record_tx(&tx);
             info!(tx_hash = tx.hash, "Transaction indexed");
         }
+
+        self.report.observe_batch(started_at.elapsed());
+        Ok(())
+    }
+
+    /// Waits until the executor completes side effects of the processed instructions
+    async fn complete_batch(&self) -> IndexingResult<()> {
+        if let Executor::Executor(executor) = &self.executor {
+            executor.lock().await.complete_batch().await?;
+        }
+        Ok(())
+    }
+
+    /// Records commitment changes of transactions and notifies the executor about them
+    #[tracing::instrument(level = "info", skip(self, updates), fields(count = %updates.len()))]
+    pub async fn process_commitment_updates(
+        &mut self,
+        updates: Vec<(String, TxCommitment)>,
+    ) -> IndexingResult<()> {
+        for (tx_hash, commitment) in updates {
+            if let Executor::Executor(executor) = &self.executor {
+                let started_at = Instant::now();
+                let control_flow = executor
+                    .lock()
+                    .await
+                    .process_commitment_change(&tx_hash, commitment)
+                    .await?;
+                self.report
+                    .observe_callback("process_commitment_change", started_at.elapsed());
+                if control_flow == ExecutorControlFlow::Stop {
+                    break;
+                }
+            }
+
+            self.db_manager
+                .update_commitment(&tx_hash, commitment)
+                .await?;
+            info!(tx_hash, commitment = ?commitment, "Transaction commitment changed");
+        }
         Ok(())
     }
 
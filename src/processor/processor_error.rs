@@ -23,8 +23,4 @@ pub type CallbackError = anyhow::Error;
 pub enum ProcessingError {
     #[error(transparent)]
     NativeProcessor(#[from] NativeProcessingError),
-    #[error(transparent)]
-    DbError(#[from] sqlx::Error),
-    #[error(transparent)]
-    CbError(#[from] CallbackError),
 }
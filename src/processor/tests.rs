@@ -1,9 +1,12 @@
 use {
     super::*,
     crate::{
-        db::{test_connection_manager::ConnectionManager, DbManager},
+        db::{
+            test_connection_manager::ConnectionManager, DbManager, IndexerDbRecording, OutboxRecord,
+        },
         fetcher::Tx,
-        Executor, ExecutorCallback, ExecutorControlFlow,
+        indexer::indexer_settings::OutboxSettings,
+        ErrorCategory, Executor, ExecutorCallback, ExecutorControlFlow, IndexerReport,
     },
     enum_extract::let_extract,
     solana_transaction_status::{parse_accounts::ParsedAccount, UiCompiledInstruction},
@@ -39,13 +42,71 @@ impl ExecutorCallback for ErrTestProcessor {
     }
 }
 
+struct OutboxProcessor;
+
+impl ExecutorCallback for OutboxProcessor {
+    async fn process_instruction_with_outbox(
+        &mut self,
+        instruction: &Instruction,
+        outbox: &mut Outbox,
+    ) -> CallbackResult<ExecutorControlFlow> {
+        outbox.push("instructions", instruction.idempotency_key());
+        Ok(ExecutorControlFlow::Pass)
+    }
+}
+
+struct UncompletedProcessor;
+
+impl ExecutorCallback for UncompletedProcessor {
+    async fn process_instruction_with_outbox(
+        &mut self,
+        instruction: &Instruction,
+        outbox: &mut Outbox,
+    ) -> CallbackResult<ExecutorControlFlow> {
+        outbox.push("instructions", instruction.idempotency_key());
+        Ok(ExecutorControlFlow::Pass)
+    }
+
+    async fn complete_batch(&mut self) -> CallbackResult<()> {
+        Err(anyhow::anyhow!("Consumer is unavailable").context(ErrorCategory::Transient))
+    }
+}
+
+#[derive(Default)]
+struct TestDelivery {
+    delivered: Vec<OutboxRecord>,
+    fail: bool,
+
+    /// Payload of the message the receiver rejects
+    rejected: Option<String>,
+
+    topic: Option<String>,
+}
+
+impl OutboxDelivery for TestDelivery {
+    async fn deliver(&mut self, message: &OutboxRecord) -> CallbackResult<()> {
+        if self.fail {
+            anyhow::bail!("Receiver is unavailable");
+        }
+        if self.rejected.as_ref() == Some(&message.payload) {
+            return Err(anyhow::anyhow!("Malformed message").context(ErrorCategory::Data));
+        }
+        self.delivered.push(message.clone());
+        Ok(())
+    }
+
+    fn topic(&self) -> Option<&str> {
+        self.topic.as_deref()
+    }
+}
+
 async fn get_processor<E>(executor: Executor<E>, options: PgConnectOptions) -> ProcessingManager<E>
 where
     E: ExecutorCallback + Send + Sync + 'static,
 {
     let db_manager = DbManager::connect(options).expect("Failed to create manager");
 
-    let mut processing_manager = ProcessingManager::<E>::new(db_manager);
+    let mut processing_manager = ProcessingManager::<E>::new(db_manager, IndexerReport::default());
     processing_manager.set_executor(executor);
 
     processing_manager
@@ -56,6 +117,7 @@ async fn get_instructions_success_test() {
     let tx = Tx::new(
         String::default(),
         123,
+        123,
         vec![UiCompiledInstruction {
             program_id_index: 0,
             accounts: vec![0],
@@ -83,6 +145,7 @@ async fn no_instruction_in_tx_test() {
     let tx = Tx::new(
         String::default(),
         123,
+        123,
         vec![],
         vec![ParsedAccount {
             pubkey: String::default(),
@@ -112,6 +175,7 @@ async fn no_acc_in_instruction_test() {
     let tx = Tx::new(
         String::default(),
         123,
+        123,
         vec![UiCompiledInstruction {
             program_id_index: 0,
             accounts: vec![],
@@ -140,6 +204,7 @@ async fn process_batch_success_test() {
     let txs = vec![Tx::new(
         String::default(),
         123,
+        123,
         vec![UiCompiledInstruction {
             program_id_index: 0,
             accounts: vec![0],
@@ -168,6 +233,7 @@ async fn process_batch_fail_test() {
     let txs = vec![Tx::new(
         String::default(),
         123,
+        123,
         vec![UiCompiledInstruction {
             program_id_index: 0,
             accounts: vec![0],
@@ -191,3 +257,185 @@ async fn process_batch_fail_test() {
         panic!("Error: {}", err);
     };
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn complete_batch_fail_test() {
+    let tx = Tx::new(
+        "uncompleted_tx".to_string(),
+        123,
+        123,
+        vec![
+            UiCompiledInstruction {
+                program_id_index: 0,
+                accounts: vec![0],
+                data: String::default(),
+                stack_height: None,
+            };
+            2
+        ],
+        vec![ParsedAccount {
+            pubkey: String::default(),
+            writable: true,
+            signer: true,
+            source: None,
+        }],
+    );
+
+    let connection_manager = ConnectionManager::build().await;
+    let options = connection_manager.get_connection_options();
+    let processor = Executor::from_executor(UncompletedProcessor);
+    let mut processing_manager = get_processor(processor, options.clone()).await;
+    let instructions = processing_manager.get_instructions(&tx).unwrap();
+
+    // The first instruction was recorded by an earlier batch
+    let db_manager = DbManager::connect(options).expect("Failed to create manager");
+    db_manager
+        .insert_instruction(&instructions[0])
+        .await
+        .unwrap();
+
+    let error = processing_manager
+        .process_batch(vec![tx])
+        .await
+        .unwrap_err();
+    assert_eq!(error.category(), ErrorCategory::Transient);
+
+    // Only instructions of the uncompleted batch are processed again, their messages are dropped
+    assert!(db_manager
+        .recorded_instruction(&instructions[0])
+        .await
+        .unwrap());
+    assert!(!db_manager
+        .recorded_instruction(&instructions[1])
+        .await
+        .unwrap());
+    assert!(db_manager
+        .get_undelivered_messages(None, 10)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn outbox_test() {
+    let tx = Tx::new(
+        "outbox_tx".to_string(),
+        123,
+        123,
+        vec![UiCompiledInstruction {
+            program_id_index: 0,
+            accounts: vec![0],
+            data: String::default(),
+            stack_height: None,
+        }],
+        vec![ParsedAccount {
+            pubkey: String::default(),
+            writable: true,
+            signer: true,
+            source: None,
+        }],
+    );
+
+    let connection_manager = ConnectionManager::build().await;
+    let options = connection_manager.get_connection_options();
+    let processor = Executor::from_executor(OutboxProcessor);
+    let mut processing_manager = get_processor(processor, options.clone()).await;
+
+    // The instruction is recorded on the first attempt, so the message is queued only once
+    processing_manager.process_tx(&tx).await.unwrap();
+    processing_manager.process_tx(&tx).await.unwrap();
+
+    let db_manager = DbManager::connect(options).expect("Failed to create manager");
+    let failing = TestDelivery {
+        fail: true,
+        ..Default::default()
+    };
+    let mut dispatcher =
+        OutboxDispatcher::new(db_manager.clone(), failing, OutboxSettings::default());
+    assert_eq!(dispatcher.dispatch_pending().await.unwrap(), 0);
+
+    let undelivered = db_manager.get_undelivered_messages(None, 10).await.unwrap();
+    assert_eq!(undelivered.len(), 1);
+    assert_eq!(undelivered[0].attempts, 1);
+    assert_eq!(
+        undelivered[0].last_error.as_deref(),
+        Some("Receiver is unavailable")
+    );
+
+    let mut dispatcher = OutboxDispatcher::new(
+        db_manager.clone(),
+        TestDelivery::default(),
+        OutboxSettings::default(),
+    );
+    assert_eq!(dispatcher.dispatch_pending().await.unwrap(), 1);
+    assert_eq!(dispatcher.dispatch_pending().await.unwrap(), 0);
+
+    let delivered = &dispatcher.delivery().delivered;
+    assert_eq!(delivered[0].idempotency_key, "outbox_tx:0:0");
+    assert_eq!(delivered[0].topic, "instructions");
+    assert_eq!(delivered[0].payload, "outbox_tx:0");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn outbox_dead_letter_test() {
+    let tx = |hash: &str| {
+        Tx::new(
+            hash.to_string(),
+            123,
+            123,
+            vec![UiCompiledInstruction {
+                program_id_index: 0,
+                accounts: vec![0],
+                data: String::default(),
+                stack_height: None,
+            }],
+            vec![ParsedAccount {
+                pubkey: String::default(),
+                writable: true,
+                signer: true,
+                source: None,
+            }],
+        )
+    };
+
+    let connection_manager = ConnectionManager::build().await;
+    let options = connection_manager.get_connection_options();
+    let processor = Executor::from_executor(OutboxProcessor);
+    let mut processing_manager = get_processor(processor, options.clone()).await;
+    processing_manager
+        .process_tx(&tx("rejected_tx"))
+        .await
+        .unwrap();
+    processing_manager
+        .process_tx(&tx("accepted_tx"))
+        .await
+        .unwrap();
+
+    // Messages of other topics are left for their deliveries
+    let db_manager = DbManager::connect(options).expect("Failed to create manager");
+    let other_topic = TestDelivery {
+        topic: Some("webhook".to_string()),
+        ..Default::default()
+    };
+    let mut dispatcher =
+        OutboxDispatcher::new(db_manager.clone(), other_topic, OutboxSettings::default());
+    assert_eq!(dispatcher.dispatch_pending().await.unwrap(), 0);
+    assert!(dispatcher.delivery().delivered.is_empty());
+
+    // The rejected message doesn't block the following one
+    let rejecting = TestDelivery {
+        rejected: Some("rejected_tx:0".to_string()),
+        topic: Some("instructions".to_string()),
+        ..Default::default()
+    };
+    let mut dispatcher =
+        OutboxDispatcher::new(db_manager.clone(), rejecting, OutboxSettings::default());
+    assert_eq!(dispatcher.dispatch_pending().await.unwrap(), 1);
+    assert_eq!(dispatcher.delivery().delivered[0].payload, "accepted_tx:0");
+
+    assert!(db_manager
+        .get_undelivered_messages(None, 10)
+        .await
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,20 @@
+#[cfg(feature = "mq")]
+pub mod mq;
+pub mod sink_event;
+#[cfg(any(feature = "webhook", feature = "mq"))]
+pub mod sink_settings;
+pub mod stream;
+#[cfg(feature = "webhook")]
+pub mod webhook;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "mq")]
+pub use mq::*;
+pub use sink_event::*;
+#[cfg(any(feature = "webhook", feature = "mq"))]
+pub use sink_settings::*;
+pub use stream::*;
+#[cfg(feature = "webhook")]
+pub use webhook::*;
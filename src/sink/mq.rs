@@ -0,0 +1,244 @@
+use {
+    crate::{
+        executor::{
+            CbResult, ControlFlowWithData, ExecutorCallback, ExecutorControlFlow, TxResult,
+        },
+        fetcher::Tx,
+        sink::{MessageKey, MqSettings, SinkEvent},
+        CallbackResult, ErrorCategory, Instruction,
+    },
+    futures::Future,
+    std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+/// A message published to the broker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrokerMessage {
+    /// Topic of the message
+    pub topic: String,
+
+    /// Partitioning key, e.g. a Kafka record key or the last token of a NATS subject
+    pub key: String,
+
+    /// Idempotency key the broker deduplicates messages by
+    pub id: String,
+
+    /// JSON serialized [SinkEvent]
+    pub payload: String,
+}
+
+/// A client of a message broker, e.g. a NATS JetStream context or a Kafka producer
+pub trait MessageBroker {
+    /// Publishes the message, resolves once the broker acknowledges it
+    fn publish(
+        &mut self,
+        message: BrokerMessage,
+    ) -> impl Future<Output = CallbackResult<()>> + Send;
+}
+
+/// An executor publishing indexed instructions and, optionally, transactions to a message broker.
+/// The indexer records an instruction only after the broker acknowledges its message,
+/// so a restarted indexer publishes again the messages that weren't acknowledged.
+pub struct MqExecutor<B> {
+    broker: B,
+    settings: MqSettings,
+}
+
+impl<B> MqExecutor<B>
+where
+    B: MessageBroker + Send + Sync,
+{
+    pub fn new(broker: B, settings: MqSettings) -> Self {
+        Self { broker, settings }
+    }
+
+    /// Returns the broker of the executor
+    pub fn broker(&self) -> &B {
+        &self.broker
+    }
+
+    async fn publish(&mut self, key: String, id: String, event: SinkEvent) -> CallbackResult<()> {
+        let message = BrokerMessage {
+            topic: self.settings.topic.clone(),
+            key,
+            id,
+            payload: serde_json::to_string(&event)?,
+        };
+
+        self.broker.publish(message).await
+    }
+
+    fn instruction_key(&self, instruction: &Instruction) -> String {
+        match self.settings.key.unwrap_or_default() {
+            MessageKey::ProgramId => instruction.program_id.clone(),
+            MessageKey::Account => instruction.account_keys.first().map_or_else(
+                || instruction.program_id.clone(),
+                |account| account.pubkey.clone(),
+            ),
+        }
+    }
+
+    fn tx_key(&self, tx: &Tx) -> String {
+        let account = match self.settings.key.unwrap_or_default() {
+            MessageKey::ProgramId => tx
+                .instructions
+                .first()
+                .and_then(|instruction| tx.account_keys.get(instruction.program_id_index as usize)),
+            MessageKey::Account => tx.account_keys.first(),
+        };
+
+        account.map_or_else(|| tx.hash.clone(), |account| account.pubkey.clone())
+    }
+}
+
+impl<B> ExecutorCallback for MqExecutor<B>
+where
+    B: MessageBroker + Send + Sync,
+{
+    async fn process_instruction(&mut self, instruction: &Instruction) -> CbResult {
+        let key = self.instruction_key(instruction);
+        self.publish(
+            key,
+            instruction.idempotency_key(),
+            SinkEvent::from(instruction),
+        )
+        .await?;
+
+        Ok(ExecutorControlFlow::Pass)
+    }
+
+    async fn process_parsed_transaction(&mut self, tx: &Tx) -> TxResult {
+        if self.settings.transactions.unwrap_or_default() {
+            let key = self.tx_key(tx);
+            self.publish(key, tx.hash.clone(), SinkEvent::from(tx))
+                .await?;
+        }
+
+        Ok(ControlFlowWithData {
+            control_flow: ExecutorControlFlow::Pass,
+            data: None,
+        })
+    }
+}
+
+/// An in-process broker keeping published messages, a stand-in for a real broker in tests.
+/// Like JetStream, it drops messages whose id was already published.
+#[derive(Clone, Default)]
+pub struct InMemoryBroker {
+    messages: Arc<Mutex<Vec<BrokerMessage>>>,
+    unavailable: Arc<AtomicBool>,
+}
+
+impl InMemoryBroker {
+    /// Returns the published messages in the order of their publishing
+    pub fn messages(&self) -> Vec<BrokerMessage> {
+        self.lock().clone()
+    }
+
+    /// Makes publishing fail with a transient error until the broker is available again
+    pub fn set_available(&self, available: bool) {
+        self.unavailable.store(!available, Ordering::Relaxed);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<BrokerMessage>> {
+        self.messages.lock().expect("Broker lock is poisoned")
+    }
+}
+
+impl MessageBroker for InMemoryBroker {
+    async fn publish(&mut self, message: BrokerMessage) -> CallbackResult<()> {
+        if self.unavailable.load(Ordering::Relaxed) {
+            return Err(anyhow::anyhow!("Broker is unavailable").context(ErrorCategory::Transient));
+        }
+
+        let mut messages = self.lock();
+        if !messages.iter().any(|published| published.id == message.id) {
+            messages.push(message);
+        }
+        Ok(())
+    }
+}
+
+/// Publishes messages to NATS JetStream on the `<topic>.<key>` subject.
+/// The message id is sent in the `Nats-Msg-Id` header, so the stream drops duplicates
+/// published within its deduplication window.
+#[cfg(feature = "nats")]
+#[derive(Clone)]
+pub struct NatsBroker {
+    jetstream: async_nats::jetstream::Context,
+}
+
+#[cfg(feature = "nats")]
+impl NatsBroker {
+    pub async fn connect(url: &str) -> CallbackResult<Self> {
+        let client = async_nats::connect(url).await?;
+
+        Ok(Self {
+            jetstream: async_nats::jetstream::new(client),
+        })
+    }
+}
+
+#[cfg(feature = "nats")]
+impl MessageBroker for NatsBroker {
+    async fn publish(&mut self, message: BrokerMessage) -> CallbackResult<()> {
+        let mut headers = async_nats::HeaderMap::new();
+        headers.insert(async_nats::header::NATS_MESSAGE_ID, message.id.as_str());
+        let subject = format!("{}.{}", message.topic, message.key);
+
+        self.jetstream
+            .publish_with_headers(subject, headers, message.payload.into_bytes().into())
+            .await
+            .map_err(|error| anyhow::Error::new(error).context(ErrorCategory::Transient))?
+            .await
+            .map_err(|error| anyhow::Error::new(error).context(ErrorCategory::Transient))?;
+
+        Ok(())
+    }
+}
+
+/// Publishes messages to Kafka with the message key as the record key, so messages of the same
+/// key land in the same partition. The message id is sent in the `id` header and the producer
+/// is idempotent, so retries of the producer don't duplicate records.
+#[cfg(feature = "kafka")]
+#[derive(Clone)]
+pub struct KafkaBroker {
+    producer: rdkafka::producer::FutureProducer,
+}
+
+#[cfg(feature = "kafka")]
+impl KafkaBroker {
+    /// Creates a producer of the comma separated `bootstrap.servers`
+    pub fn connect(brokers: &str) -> CallbackResult<Self> {
+        let producer = rdkafka::ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .set("enable.idempotence", "true")
+            .create()?;
+
+        Ok(Self { producer })
+    }
+}
+
+#[cfg(feature = "kafka")]
+impl MessageBroker for KafkaBroker {
+    async fn publish(&mut self, message: BrokerMessage) -> CallbackResult<()> {
+        let headers = rdkafka::message::OwnedHeaders::new().insert(rdkafka::message::Header {
+            key: "id",
+            value: Some(&message.id),
+        });
+        let record = rdkafka::producer::FutureRecord::to(&message.topic)
+            .key(&message.key)
+            .payload(&message.payload)
+            .headers(headers);
+
+        self.producer
+            .send(record, rdkafka::util::Timeout::Never)
+            .await
+            .map_err(|(error, _)| anyhow::Error::new(error).context(ErrorCategory::Transient))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+use {
+    crate::{fetcher::Tx, Instruction},
+    serde::Serialize,
+};
+
+/// Indexed data serialized into JSON by sink executors
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SinkEvent {
+    Transaction {
+        signature: String,
+        slot: u64,
+        blocktime: i64,
+        accounts: Vec<String>,
+    },
+    Instruction {
+        /// Idempotency key of the instruction
+        key: String,
+        signature: String,
+        index: u8,
+        program_id: String,
+        blocktime: i64,
+        accounts: Vec<String>,
+        data: String,
+    },
+}
+
+impl From<&Tx> for SinkEvent {
+    fn from(tx: &Tx) -> Self {
+        SinkEvent::Transaction {
+            signature: tx.hash.clone(),
+            slot: tx.slot,
+            blocktime: tx.blocktime,
+            accounts: tx
+                .account_keys
+                .iter()
+                .map(|account| account.pubkey.clone())
+                .collect(),
+        }
+    }
+}
+
+impl From<&Instruction> for SinkEvent {
+    fn from(instruction: &Instruction) -> Self {
+        SinkEvent::Instruction {
+            key: instruction.idempotency_key(),
+            signature: instruction.tx_hash.clone(),
+            index: instruction.id,
+            program_id: instruction.program_id.clone(),
+            blocktime: instruction.blocktime,
+            accounts: instruction
+                .account_keys
+                .iter()
+                .map(|account| account.pubkey.clone())
+                .collect(),
+            data: instruction.data.clone(),
+        }
+    }
+}
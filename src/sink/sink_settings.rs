@@ -0,0 +1,104 @@
+#[cfg(feature = "webhook")]
+use secrecy::Secret;
+use serde::Deserialize;
+
+/// Settings of the webhook sink
+#[cfg(feature = "webhook")]
+#[derive(Deserialize, Clone, Debug)]
+pub struct WebhookSettings {
+    /// URL the events are POSTed to
+    pub url: String,
+
+    /// Key of the HMAC-SHA256 signature sent in the `X-Signature-256` header,
+    /// requests are not signed if absent
+    pub secret: Option<Secret<String>>,
+
+    /// Maximum amount of instructions in a single request (default - 100)
+    pub batch_size: Option<usize>,
+
+    /// Amount of retries of a failed request (default - 3)
+    pub max_retries: Option<u32>,
+
+    /// Delay before the first retry in milliseconds,
+    /// delays of consecutive retries grow as Fibonacci numbers (default - 500)
+    pub retry_delay: Option<u64>,
+
+    /// Request timeout in seconds (default - 10)
+    pub timeout: Option<u64>,
+
+    /// Whether to POST fetched transactions besides instructions (default - false)
+    pub transactions: Option<bool>,
+}
+
+#[cfg(feature = "webhook")]
+impl WebhookSettings {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            secret: None,
+            batch_size: None,
+            max_retries: None,
+            retry_delay: None,
+            timeout: None,
+            transactions: None,
+        }
+    }
+
+    pub(crate) fn batch_size(&self) -> usize {
+        self.batch_size.unwrap_or(100)
+    }
+
+    pub(crate) fn max_retries(&self) -> u32 {
+        self.max_retries.unwrap_or(3)
+    }
+
+    pub(crate) fn retry_delay(&self) -> u64 {
+        self.retry_delay.unwrap_or(500)
+    }
+
+    pub(crate) fn timeout(&self) -> u64 {
+        self.timeout.unwrap_or(10)
+    }
+
+    pub(crate) fn transactions(&self) -> bool {
+        self.transactions.unwrap_or_default()
+    }
+}
+
+/// Settings of the message queue sink
+#[cfg(feature = "mq")]
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MqSettings {
+    /// Topic the events are published to
+    pub topic: String,
+
+    /// Value the messages are keyed by (default - program_id)
+    pub key: Option<MessageKey>,
+
+    /// Whether to publish fetched transactions besides instructions (default - false)
+    pub transactions: Option<bool>,
+}
+
+#[cfg(feature = "mq")]
+impl MqSettings {
+    pub fn new(topic: impl Into<String>) -> Self {
+        Self {
+            topic: topic.into(),
+            key: None,
+            transactions: None,
+        }
+    }
+}
+
+/// Value the published messages are keyed by, messages with the same key keep their order
+#[cfg(feature = "mq")]
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageKey {
+    /// The invoked program, the program of the first instruction for transactions
+    #[default]
+    ProgramId,
+
+    /// The first account of the instruction, the fee payer for transactions
+    Account,
+}
@@ -0,0 +1,169 @@
+use {
+    crate::{
+        executor::{
+            CbResult, ControlFlowWithData, ExecutorCallback, ExecutorControlFlow, TxResult,
+        },
+        fetcher::Tx,
+        CallbackError, CallbackResult, ErrorCategory, Instruction,
+    },
+    futures::Stream,
+    std::{
+        collections::VecDeque,
+        pin::Pin,
+        task::{Context, Poll},
+    },
+    tokio::sync::{mpsc, oneshot},
+};
+
+/// Indexed data published to the consumer
+#[derive(Debug, Clone)]
+pub enum StreamEvent {
+    /// A fetched transaction, published before its instructions
+    Transaction(Tx),
+
+    /// An instruction of the program
+    Instruction(Instruction),
+}
+
+/// An event waiting for the acknowledgement of the consumer.
+/// The indexer doesn't record the batch of the event until it is acknowledged,
+/// an event dropped without acknowledgement fails the indexing with a transient error.
+#[derive(Debug)]
+pub struct StreamItem {
+    event: StreamEvent,
+    ack: oneshot::Sender<CallbackResult<()>>,
+}
+
+impl StreamItem {
+    /// Returns the published event
+    pub fn event(&self) -> &StreamEvent {
+        &self.event
+    }
+
+    /// Takes the published event, the item can still be acknowledged
+    pub fn into_parts(self) -> (StreamEvent, Acknowledgement) {
+        (self.event, Acknowledgement(self.ack))
+    }
+
+    /// Confirms the event is consumed, so the indexer may move past it
+    pub fn ack(self) {
+        Acknowledgement(self.ack).ack();
+    }
+
+    /// Rejects the event, the indexer fails with the error
+    pub fn nack(self, error: CallbackError) {
+        Acknowledgement(self.ack).nack(error);
+    }
+}
+
+/// Acknowledgement of an event taken out of a [StreamItem]
+#[derive(Debug)]
+pub struct Acknowledgement(oneshot::Sender<CallbackResult<()>>);
+
+impl Acknowledgement {
+    /// Confirms the event is consumed, so the indexer may move past it
+    pub fn ack(self) {
+        // The indexer may have stopped waiting, there is nothing to confirm then
+        let _ = self.0.send(Ok(()));
+    }
+
+    /// Rejects the event, the indexer fails with the error
+    pub fn nack(self, error: CallbackError) {
+        let _ = self.0.send(Err(error));
+    }
+}
+
+/// An executor publishing indexed transactions and instructions into a bounded channel.
+/// Publishing waits while the channel is full, so fetching pauses when the consumer falls behind.
+/// Acknowledgements are awaited once the batch is processed, so the indexing never moves past
+/// unacknowledged events.
+pub struct StreamingExecutor {
+    sender: mpsc::Sender<StreamItem>,
+
+    /// Acknowledgements of the events published within the batch, in the order of publishing
+    pending: VecDeque<oneshot::Receiver<CallbackResult<()>>>,
+}
+
+impl StreamingExecutor {
+    /// Creates the executor and the receiving end of its channel
+    pub fn channel(capacity: usize) -> (Self, mpsc::Receiver<StreamItem>) {
+        let (sender, receiver) = mpsc::channel(capacity);
+        let executor = Self {
+            sender,
+            pending: VecDeque::new(),
+        };
+        (executor, receiver)
+    }
+
+    /// Creates the executor and a stream of its events
+    pub fn stream(capacity: usize) -> (Self, IndexedStream) {
+        let (executor, receiver) = Self::channel(capacity);
+        (executor, IndexedStream { receiver })
+    }
+
+    /// Publishes the event, its acknowledgement is awaited when the batch is completed
+    async fn publish(&mut self, event: StreamEvent) -> CallbackResult<()> {
+        let (ack, acknowledged) = oneshot::channel();
+        self.sender
+            .send(StreamItem { event, ack })
+            .await
+            .map_err(|_| {
+                anyhow::anyhow!("Stream consumer is closed").context(ErrorCategory::Fatal)
+            })?;
+
+        self.pending.push_back(acknowledged);
+        Ok(())
+    }
+}
+
+impl ExecutorCallback for StreamingExecutor {
+    async fn process_instruction(&mut self, instruction: &Instruction) -> CbResult {
+        self.publish(StreamEvent::Instruction(instruction.clone()))
+            .await?;
+        Ok(ExecutorControlFlow::Pass)
+    }
+
+    async fn process_parsed_transaction(&mut self, tx: &Tx) -> TxResult {
+        self.publish(StreamEvent::Transaction(tx.clone())).await?;
+        Ok(ControlFlowWithData {
+            control_flow: ExecutorControlFlow::Pass,
+            data: None,
+        })
+    }
+
+    /// Waits for acknowledgements of the published events, the first rejection is returned
+    async fn complete_batch(&mut self) -> CallbackResult<()> {
+        // Receivers are removed only once resolved, so a cancelled wait can be resumed
+        while let Some(acknowledged) = self.pending.front_mut() {
+            let result = acknowledged.await;
+            self.pending.pop_front();
+
+            let result = result.unwrap_or_else(|_| {
+                Err(
+                    anyhow::anyhow!("Stream item was dropped without acknowledgement")
+                        .context(ErrorCategory::Transient),
+                )
+            });
+            if let Err(error) = result {
+                // The whole batch is processed again
+                self.pending.clear();
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Indexed events of a [StreamingExecutor] as a [Stream]
+#[derive(Debug)]
+pub struct IndexedStream {
+    receiver: mpsc::Receiver<StreamItem>,
+}
+
+impl Stream for IndexedStream {
+    type Item = StreamItem;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
@@ -0,0 +1,459 @@
+use {
+    super::*,
+    crate::{
+        executor::ExecutorCallback, fetcher::Tx, indexer::callback_error_category, ErrorCategory,
+        ExecutorControlFlow, Instruction,
+    },
+    futures::StreamExt,
+    std::time::Duration,
+    tokio::time::timeout,
+};
+
+fn get_instruction(id: u8) -> Instruction {
+    Instruction::new(
+        id,
+        "stream_tx".to_string(),
+        "program".to_string(),
+        123,
+        vec![],
+        String::default(),
+    )
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn stream_ack_test() {
+    let (mut executor, mut stream) = StreamingExecutor::stream(1);
+
+    let consumer = tokio::spawn(async move {
+        let mut events = vec![];
+        while let Some(item) = stream.next().await {
+            let (event, ack) = item.into_parts();
+            events.push(event);
+            ack.ack();
+        }
+        events
+    });
+
+    let tx = Tx::new("stream_tx".to_string(), 123, 123, vec![], vec![]);
+    let result = executor.process_parsed_transaction(&tx).await.unwrap();
+    assert_eq!(result.control_flow, ExecutorControlFlow::Pass);
+
+    for id in 0..3 {
+        let control_flow = executor
+            .process_instruction(&get_instruction(id))
+            .await
+            .unwrap();
+        assert_eq!(control_flow, ExecutorControlFlow::Pass);
+    }
+    executor.complete_batch().await.unwrap();
+    drop(executor);
+
+    let events = consumer.await.unwrap();
+    assert_eq!(events.len(), 4);
+    assert!(matches!(&events[0], StreamEvent::Transaction(tx) if tx.hash == "stream_tx"));
+    assert!(matches!(&events[3], StreamEvent::Instruction(instruction) if instruction.id == 2));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn stream_backpressure_test() {
+    let (mut executor, mut receiver) = StreamingExecutor::channel(1);
+
+    // Publishing waits while the channel is full
+    executor
+        .process_instruction(&get_instruction(0))
+        .await
+        .unwrap();
+    let instruction = get_instruction(1);
+    let publishing = timeout(
+        Duration::from_millis(100),
+        executor.process_instruction(&instruction),
+    );
+    assert!(publishing.await.is_err());
+
+    // The batch is completed once the consumer acknowledges its events
+    let item = receiver.recv().await.unwrap();
+    assert!(matches!(item.event(), StreamEvent::Instruction(_)));
+    let completing = timeout(Duration::from_millis(100), executor.complete_batch());
+    assert!(completing.await.is_err());
+    item.ack();
+    executor.complete_batch().await.unwrap();
+
+    // Events are published without waiting for acknowledgements of the previous ones
+    executor.process_instruction(&instruction).await.unwrap();
+    let item = receiver.recv().await.unwrap();
+    executor
+        .process_instruction(&get_instruction(2))
+        .await
+        .unwrap();
+    item.nack(anyhow::anyhow!("Consumer failed"));
+    receiver.recv().await.unwrap().ack();
+    let error = executor.complete_batch().await.unwrap_err();
+    assert_eq!(error.to_string(), "Consumer failed");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn stream_consumer_failure_test() {
+    let (mut executor, mut receiver) = StreamingExecutor::channel(1);
+
+    // The item is dropped without acknowledgement
+    executor
+        .process_instruction(&get_instruction(0))
+        .await
+        .unwrap();
+    receiver.recv().await.unwrap();
+    let error = executor.complete_batch().await.unwrap_err();
+    assert_eq!(callback_error_category(&error), ErrorCategory::Transient);
+
+    drop(receiver);
+    let error = executor
+        .process_instruction(&get_instruction(1))
+        .await
+        .unwrap_err();
+    assert_eq!(callback_error_category(&error), ErrorCategory::Fatal);
+}
+
+#[cfg(feature = "webhook")]
+mod webhook {
+    use {
+        super::*,
+        crate::{
+            db::{test_connection_manager::ConnectionManager, DbManager, IndexerDbRecording},
+            indexer::indexer_settings::OutboxSettings,
+            processor::{Outbox, OutboxDispatcher, ProcessingManager},
+            Executor, IndexerReport,
+        },
+        hmac::{Hmac, Mac},
+        secrecy::Secret,
+        sha2::Sha256,
+        solana_transaction_status::{parse_accounts::ParsedAccount, UiCompiledInstruction},
+        std::{
+            collections::{HashMap, VecDeque},
+            sync::{Arc, Mutex},
+        },
+        tokio::{
+            io::{AsyncReadExt, AsyncWriteExt},
+            net::TcpListener,
+        },
+    };
+
+    struct Request {
+        headers: HashMap<String, String>,
+        body: String,
+    }
+
+    /// A local HTTP server answering with scripted statuses, 200 once they run out
+    struct StandInServer {
+        url: String,
+        requests: Arc<Mutex<Vec<Request>>>,
+    }
+
+    impl StandInServer {
+        async fn start(statuses: Vec<u16>) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("http://{}/hook", listener.local_addr().unwrap());
+            let requests = Arc::<Mutex<Vec<Request>>>::default();
+            let mut statuses = VecDeque::from(statuses);
+
+            let received = requests.clone();
+            tokio::spawn(async move {
+                loop {
+                    let (mut socket, _) = listener.accept().await.unwrap();
+                    let request = read_request(&mut socket).await;
+                    received.lock().unwrap().push(request);
+
+                    let status = statuses.pop_front().unwrap_or(200);
+                    let response = format!(
+                        "HTTP/1.1 {status} Status\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    );
+                    socket.write_all(response.as_bytes()).await.unwrap();
+                }
+            });
+
+            Self { url, requests }
+        }
+    }
+
+    async fn read_request(socket: &mut tokio::net::TcpStream) -> Request {
+        let mut data = vec![];
+        let mut buffer = [0; 4096];
+        let header_end = loop {
+            let read = socket.read(&mut buffer).await.unwrap();
+            data.extend_from_slice(&buffer[..read]);
+            if let Some(end) = data.windows(4).position(|window| window == b"\r\n\r\n") {
+                break end + 4;
+            }
+        };
+
+        let head = String::from_utf8_lossy(&data[..header_end]).to_string();
+        let headers = head
+            .lines()
+            .skip(1)
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+            .collect::<HashMap<_, _>>();
+
+        let length = headers
+            .get("content-length")
+            .map_or(0, |length| length.parse().unwrap());
+        while data.len() < header_end + length {
+            let read = socket.read(&mut buffer).await.unwrap();
+            data.extend_from_slice(&buffer[..read]);
+        }
+
+        Request {
+            headers,
+            body: String::from_utf8_lossy(&data[header_end..]).to_string(),
+        }
+    }
+
+    fn get_settings(url: &str) -> WebhookSettings {
+        WebhookSettings {
+            secret: Some(Secret::new("secret".into())),
+            retry_delay: Some(1),
+            ..WebhookSettings::new(url)
+        }
+    }
+
+    fn get_tx() -> Tx {
+        let instruction = UiCompiledInstruction {
+            program_id_index: 0,
+            accounts: vec![0],
+            data: String::default(),
+            stack_height: None,
+        };
+        Tx::new(
+            "webhook_tx".to_string(),
+            123,
+            123,
+            vec![instruction.clone(), instruction],
+            vec![ParsedAccount {
+                pubkey: String::default(),
+                writable: true,
+                signer: true,
+                source: None,
+            }],
+        )
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn webhook_delivery_test() {
+        let server = StandInServer::start(vec![500]).await;
+        let connection_manager = ConnectionManager::build().await;
+        let db_manager = DbManager::connect(connection_manager.get_connection_options())
+            .expect("Failed to create manager");
+        let client = WebhookClient::new(db_manager.clone(), get_settings(&server.url)).unwrap();
+
+        let mut processing_manager =
+            ProcessingManager::new(db_manager.clone(), IndexerReport::default());
+        processing_manager.set_executor(Executor::from_executor(WebhookExecutor::new(
+            client.clone(),
+        )));
+        processing_manager.process_tx(&get_tx()).await.unwrap();
+
+        let mut dispatcher =
+            OutboxDispatcher::new(db_manager.clone(), client, OutboxSettings::default());
+        assert_eq!(dispatcher.dispatch_pending().await.unwrap(), 2);
+
+        // The first request fails and is retried
+        let requests = std::mem::take(&mut *server.requests.lock().unwrap());
+        assert_eq!(requests.len(), 2);
+        let request = &requests[1];
+        assert_eq!(request.body, requests[0].body);
+
+        let events: Vec<serde_json::Value> = serde_json::from_str(&request.body).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0]["type"], "instruction");
+        assert_eq!(events[0]["key"], "webhook_tx:0");
+        assert_eq!(events[1]["index"], 1);
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"secret").unwrap();
+        mac.update(request.body.as_bytes());
+        let signature = format!("sha256={}", hex::encode(mac.finalize().into_bytes()));
+        assert_eq!(
+            request.headers.get(&SIGNATURE_HEADER.to_lowercase()),
+            Some(&signature)
+        );
+
+        let deliveries = db_manager.get_webhook_deliveries(10).await.unwrap();
+        assert_eq!(deliveries.len(), 2);
+        assert_eq!(deliveries[0].attempt, 2);
+        assert_eq!(deliveries[0].status, Some(200));
+        assert_eq!(deliveries[0].error, None);
+        assert_eq!(deliveries[1].status, Some(500));
+        assert_eq!(deliveries[0].keys, vec!["webhook_tx:0:0", "webhook_tx:1:0"]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn webhook_rejection_test() {
+        let server = StandInServer::start(vec![400]).await;
+        let connection_manager = ConnectionManager::build().await;
+        let db_manager = DbManager::connect(connection_manager.get_connection_options())
+            .expect("Failed to create manager");
+        let settings = WebhookSettings {
+            transactions: Some(true),
+            ..get_settings(&server.url)
+        };
+        let mut executor =
+            WebhookExecutor::new(WebhookClient::new(db_manager.clone(), settings).unwrap());
+
+        // Rejected requests are not retried
+        let error = executor
+            .process_parsed_transaction(&get_tx())
+            .await
+            .unwrap_err();
+        assert_eq!(callback_error_category(&error), ErrorCategory::Data);
+        assert_eq!(server.requests.lock().unwrap().len(), 1);
+
+        let deliveries = db_manager.get_webhook_deliveries(10).await.unwrap();
+        assert_eq!(deliveries.len(), 1);
+        assert_eq!(deliveries[0].keys, vec!["webhook_tx"]);
+        assert_eq!(deliveries[0].status, Some(400));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn webhook_outbox_rejection_test() {
+        let server = StandInServer::start(vec![400]).await;
+        let connection_manager = ConnectionManager::build().await;
+        let db_manager = DbManager::connect(connection_manager.get_connection_options())
+            .expect("Failed to create manager");
+        let settings = WebhookSettings {
+            batch_size: Some(1),
+            ..get_settings(&server.url)
+        };
+        let client = WebhookClient::new(db_manager.clone(), settings).unwrap();
+
+        // A message of another topic is left for its own delivery
+        let instruction = get_instruction(0);
+        let mut outbox = Outbox::new(&instruction);
+        outbox.push("other", "{}");
+        db_manager
+            .insert_instruction_with_outbox(&instruction, &outbox, None)
+            .await
+            .unwrap();
+
+        let mut processing_manager =
+            ProcessingManager::new(db_manager.clone(), IndexerReport::default());
+        processing_manager.set_executor(Executor::from_executor(WebhookExecutor::new(
+            client.clone(),
+        )));
+        processing_manager.process_tx(&get_tx()).await.unwrap();
+
+        // The rejected message is dead-lettered instead of blocking the following one
+        let mut dispatcher =
+            OutboxDispatcher::new(db_manager.clone(), client, OutboxSettings::default());
+        assert_eq!(dispatcher.dispatch_pending().await.unwrap(), 1);
+        assert_eq!(dispatcher.dispatch_pending().await.unwrap(), 0);
+        assert_eq!(server.requests.lock().unwrap().len(), 2);
+
+        let undelivered = db_manager.get_undelivered_messages(None, 10).await.unwrap();
+        assert_eq!(undelivered.len(), 1);
+        assert_eq!(undelivered[0].topic, "other");
+    }
+}
+
+#[cfg(feature = "mq")]
+mod mq {
+    use {
+        super::*,
+        crate::{
+            db::{test_connection_manager::ConnectionManager, DbManager, IndexerDbRecording},
+            processor::ProcessingManager,
+            Executor, IndexerReport,
+        },
+        solana_transaction_status::{parse_accounts::ParsedAccount, UiCompiledInstruction},
+    };
+
+    fn get_tx() -> Tx {
+        let instruction = UiCompiledInstruction {
+            program_id_index: 1,
+            accounts: vec![0, 1],
+            data: String::default(),
+            stack_height: None,
+        };
+        let account = |pubkey: &str| ParsedAccount {
+            pubkey: pubkey.to_string(),
+            writable: true,
+            signer: true,
+            source: None,
+        };
+        Tx::new(
+            "mq_tx".to_string(),
+            123,
+            123,
+            vec![instruction.clone(), instruction],
+            vec![account("payer"), account("")],
+        )
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn mq_acknowledgement_test() {
+        let connection_manager = ConnectionManager::build().await;
+        let db_manager = DbManager::connect(connection_manager.get_connection_options())
+            .expect("Failed to create manager");
+        let broker = InMemoryBroker::default();
+        let executor = MqExecutor::new(broker.clone(), MqSettings::new("indexer.events"));
+
+        let mut processing_manager =
+            ProcessingManager::new(db_manager.clone(), IndexerReport::default());
+        processing_manager.set_executor(Executor::from_executor(executor));
+        let tx = get_tx();
+        let instructions = processing_manager.get_instructions(&tx).unwrap();
+
+        // Instructions are not recorded until the broker acknowledges them
+        broker.set_available(false);
+        let error = processing_manager.process_tx(&tx).await.unwrap_err();
+        assert_eq!(error.category(), ErrorCategory::Transient);
+        assert!(!db_manager
+            .recorded_instruction(&instructions[0])
+            .await
+            .unwrap());
+        assert!(broker.messages().is_empty());
+
+        broker.set_available(true);
+        processing_manager.process_tx(&tx).await.unwrap();
+        processing_manager.process_tx(&tx).await.unwrap();
+        assert!(db_manager
+            .recorded_instruction(&instructions[1])
+            .await
+            .unwrap());
+
+        let messages = broker.messages();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].topic, "indexer.events");
+        assert_eq!(messages[0].key, instructions[0].program_id);
+        assert_eq!(messages[1].id, "mq_tx:1");
+
+        let event: serde_json::Value = serde_json::from_str(&messages[1].payload).unwrap();
+        assert_eq!(event["type"], "instruction");
+        assert_eq!(event["index"], 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn mq_account_key_test() {
+        let broker = InMemoryBroker::default();
+        let settings = MqSettings {
+            key: Some(MessageKey::Account),
+            transactions: Some(true),
+            ..MqSettings::new("indexer.events")
+        };
+        let mut executor = MqExecutor::new(broker.clone(), settings);
+
+        executor
+            .process_parsed_transaction(&get_tx())
+            .await
+            .unwrap();
+        let mut instruction = get_instruction(0);
+        instruction.account_keys = get_tx().account_keys;
+        executor.process_instruction(&instruction).await.unwrap();
+
+        let messages = broker.messages();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].id, "mq_tx");
+        assert_eq!(messages[0].key, "payer");
+        assert_eq!(messages[1].key, "payer");
+
+        let event: serde_json::Value = serde_json::from_str(&messages[0].payload).unwrap();
+        assert_eq!(event["type"], "transaction");
+        assert_eq!(event["slot"], 123);
+    }
+}
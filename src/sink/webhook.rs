@@ -0,0 +1,201 @@
+use {
+    crate::{
+        db::{DbManager, IndexerDbRecording, OutboxRecord, WebhookDeliveryRecord},
+        executor::{
+            CbResult, ControlFlowWithData, ExecutorCallback, ExecutorControlFlow, TxResult,
+        },
+        fetcher::Tx,
+        processor::{Outbox, OutboxDelivery},
+        sink::{SinkEvent, WebhookSettings},
+        utils::fibonacci,
+        CallbackResult, ErrorCategory, Instruction,
+    },
+    chrono::Utc,
+    hmac::{Hmac, Mac},
+    reqwest::{header::CONTENT_TYPE, Client, StatusCode},
+    secrecy::ExposeSecret,
+    sha2::Sha256,
+    std::time::Duration,
+    tokio::time::sleep,
+    tracing::{debug, warn},
+};
+
+/// Topic of outbox messages delivered by the webhook
+pub const WEBHOOK_TOPIC: &str = "webhook";
+
+/// Header carrying the HMAC-SHA256 signature of the request body
+pub const SIGNATURE_HEADER: &str = "X-Signature-256";
+
+/// Signs and POSTs batches of events to the configured URL, every attempt is logged
+/// in the `webhook_deliveries` table. Delivers instructions queued into the outbox
+/// under [WEBHOOK_TOPIC] when passed to an [OutboxDispatcher](crate::OutboxDispatcher).
+#[derive(Clone)]
+pub struct WebhookClient {
+    client: Client,
+    db_manager: DbManager,
+    settings: WebhookSettings,
+}
+
+impl WebhookClient {
+    pub fn new(db_manager: DbManager, settings: WebhookSettings) -> CallbackResult<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(settings.timeout()))
+            .build()?;
+
+        Ok(Self {
+            client,
+            db_manager,
+            settings,
+        })
+    }
+
+    /// POSTs the events as a JSON array, retrying failed requests with a growing delay.
+    /// `keys` identify the events in the delivery log.
+    #[tracing::instrument(level = "debug", skip(self, body), fields(url = self.settings.url))]
+    pub async fn post(&self, body: String, keys: Vec<String>) -> CallbackResult<()> {
+        let signature = self.sign(&body);
+        let mut attempt = 0;
+
+        loop {
+            attempt += 1;
+            let mut request = self
+                .client
+                .post(&self.settings.url)
+                .header(CONTENT_TYPE, "application/json")
+                .body(body.clone());
+            if let Some(signature) = &signature {
+                request = request.header(SIGNATURE_HEADER, signature);
+            }
+
+            let (status, error) = match request.send().await {
+                Ok(response) if response.status().is_success() => (Some(response.status()), None),
+                Ok(response) => (
+                    Some(response.status()),
+                    Some(format!("Webhook responded with {}", response.status())),
+                ),
+                Err(error) => (error.status(), Some(error.to_string())),
+            };
+            self.log(&keys, attempt, status, error.clone()).await?;
+
+            let Some(error) = error else {
+                debug!(attempt, count = keys.len(), "Webhook delivered");
+                return Ok(());
+            };
+
+            let retriable = status.map_or(true, |status| {
+                status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+            });
+            if !retriable {
+                return Err(anyhow::anyhow!(error).context(ErrorCategory::Data));
+            }
+            if attempt > self.settings.max_retries() {
+                return Err(anyhow::anyhow!(error).context(ErrorCategory::Transient));
+            }
+
+            warn!(error, attempt, "Webhook delivery failed");
+            sleep(Duration::from_millis(
+                self.settings.retry_delay() * fibonacci(attempt as u64),
+            ))
+            .await;
+        }
+    }
+
+    /// Returns `sha256=<hex digest>` of the body if a secret is configured
+    fn sign(&self, body: &str) -> Option<String> {
+        let secret = self.settings.secret.as_ref()?;
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.expose_secret().as_bytes())
+            .expect("HMAC accepts keys of any size");
+        mac.update(body.as_bytes());
+
+        Some(format!(
+            "sha256={}",
+            hex::encode(mac.finalize().into_bytes())
+        ))
+    }
+
+    async fn log(
+        &self,
+        keys: &[String],
+        attempt: u32,
+        status: Option<StatusCode>,
+        error: Option<String>,
+    ) -> CallbackResult<()> {
+        let record = WebhookDeliveryRecord {
+            url: self.settings.url.clone(),
+            keys: keys.to_vec(),
+            attempt: attempt as i32,
+            status: status.map(|status| status.as_u16() as i16),
+            error,
+            occurred_at: Utc::now().timestamp(),
+        };
+        self.db_manager.insert_webhook_delivery(&record).await?;
+
+        Ok(())
+    }
+}
+
+impl OutboxDelivery for WebhookClient {
+    async fn deliver(&mut self, message: &OutboxRecord) -> CallbackResult<()> {
+        self.deliver_batch(std::slice::from_ref(message)).await
+    }
+
+    fn topic(&self) -> Option<&str> {
+        Some(WEBHOOK_TOPIC)
+    }
+
+    fn max_batch(&self) -> usize {
+        self.settings.batch_size()
+    }
+
+    async fn deliver_batch(&mut self, messages: &[OutboxRecord]) -> CallbackResult<()> {
+        let payloads = messages
+            .iter()
+            .map(|message| message.payload.as_str())
+            .collect::<Vec<_>>();
+        let keys = messages
+            .iter()
+            .map(|message| message.idempotency_key.clone())
+            .collect();
+
+        self.post(format!("[{}]", payloads.join(",")), keys).await
+    }
+}
+
+/// An executor POSTing indexed instructions and, optionally, transactions to a webhook.
+/// Instructions are queued into the outbox together with their records and delivered in batches
+/// by an [OutboxDispatcher](crate::OutboxDispatcher) running the [WebhookClient].
+/// Transactions are delivered right after they are fetched.
+pub struct WebhookExecutor {
+    client: WebhookClient,
+}
+
+impl WebhookExecutor {
+    pub fn new(client: WebhookClient) -> Self {
+        Self { client }
+    }
+}
+
+impl ExecutorCallback for WebhookExecutor {
+    async fn process_instruction_with_outbox(
+        &mut self,
+        instruction: &Instruction,
+        outbox: &mut Outbox,
+    ) -> CbResult {
+        let payload = serde_json::to_string(&SinkEvent::from(instruction))?;
+        outbox.push(WEBHOOK_TOPIC, payload);
+
+        Ok(ExecutorControlFlow::Pass)
+    }
+
+    async fn process_parsed_transaction(&mut self, tx: &Tx) -> TxResult {
+        if self.client.settings.transactions() {
+            let body = serde_json::to_string(&[SinkEvent::from(tx)])?;
+            self.client.post(body, vec![tx.hash.clone()]).await?;
+        }
+
+        Ok(ControlFlowWithData {
+            control_flow: ExecutorControlFlow::Pass,
+            data: None,
+        })
+    }
+}
@@ -0,0 +1,61 @@
+use {
+    opentelemetry::{trace::TraceError, KeyValue},
+    opentelemetry_otlp::WithExportConfig,
+    opentelemetry_sdk::{runtime, trace, Resource},
+    thiserror::Error,
+    tracing_subscriber::{
+        layer::SubscriberExt, util::SubscriberInitExt, util::TryInitError, EnvFilter,
+    },
+};
+
+/// Errors of the tracing setup
+#[derive(Error, Debug)]
+pub enum TelemetryError {
+    #[error("Failed to install OTLP exporter: {0}")]
+    Exporter(#[from] TraceError),
+    #[error("Failed to install tracing subscriber: {0}")]
+    Subscriber(#[from] TryInitError),
+}
+
+/// Flushes spans that are not exported yet when dropped
+pub struct TelemetryGuard;
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        opentelemetry::global::shutdown_tracer_provider();
+    }
+}
+
+/// Installs a global tracing subscriber that prints logs and exports spans over OTLP/gRPC.
+///
+/// Spans are sent to `endpoint`, or to `OTEL_EXPORTER_OTLP_ENDPOINT` if it is absent
+/// (default - `http://localhost:4317`). The log level is taken from `RUST_LOG` (default - info).
+/// Must be called within a tokio runtime, the returned guard should be kept until shutdown.
+pub fn init_telemetry(
+    service_name: &str,
+    endpoint: Option<&str>,
+) -> Result<TelemetryGuard, TelemetryError> {
+    let mut exporter = opentelemetry_otlp::new_exporter().tonic();
+    if let Some(endpoint) = endpoint {
+        exporter = exporter.with_endpoint(endpoint);
+    }
+
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(exporter)
+        .with_trace_config(
+            trace::config().with_resource(Resource::new(vec![KeyValue::new(
+                "service.name",
+                service_name.to_string(),
+            )])),
+        )
+        .install_batch(runtime::Tokio)?;
+
+    tracing_subscriber::registry()
+        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
+        .with(tracing_subscriber::fmt::layer())
+        .with(tracing_opentelemetry::layer().with_tracer(tracer))
+        .try_init()?;
+
+    Ok(TelemetryGuard)
+}